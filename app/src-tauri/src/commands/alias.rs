@@ -27,7 +27,7 @@ fn get_secrets_file_path() -> PathBuf {
     home.join(".zshrc.secrets")
 }
 
-fn parse_alias_line(line: &str) -> Option<Alias> {
+pub(crate) fn parse_alias_line(line: &str) -> Option<Alias> {
     let line = line.trim();
 
     // Skip comments and empty lines
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::function::{add_function, delete_function, list_functions};
+use super::git::get_zsh_config_dir;
+use super::hooks::check_zsh_syntax;
+use super::plugin_managers::{get_zshrc_local_path, read_config, relative_to_home, write_config};
+
+/// A function stored as its own file, the file holding the function body
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AutoloadFunction {
+    pub name: String,
+    pub content: String,
+    pub shared: bool,
+    /// File relative to the home directory
+    pub file: String,
+}
+
+/// ~/.zsh/functions (versioned) or ~/.zsh/functions.local
+pub(crate) fn get_autoload_dir(shared: bool) -> PathBuf {
+    if shared {
+        get_zsh_config_dir().join("functions")
+    } else {
+        get_zsh_config_dir().join("functions.local")
+    }
+}
+
+/// The file holding the fpath and autoload lines: config.zsh for shared functions, .zshrc.local otherwise
+fn wiring_path(shared: bool) -> PathBuf {
+    if shared {
+        get_zsh_config_dir().join("config.zsh")
+    } else {
+        get_zshrc_local_path()
+    }
+}
+
+fn fpath_line(shared: bool) -> String {
+    let dir = if shared { "functions" } else { "functions.local" };
+    format!(r#"fpath=("$HOME/.zsh/{}" $fpath)"#, dir)
+}
+
+/// A function name that is also a safe file name inside the autoload directory
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && !name.contains('/')
+        && !name.starts_with(['.', '-'])
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || "_-.:".contains(c));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid function name '{}'", name))
+    }
+}
+
+fn function_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_file())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| validate_name(name).is_ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Rewrite the fpath and `autoload -Uz` lines for a directory so they name every function in it,
+/// removing them once the directory is empty
+fn sync_wiring(shared: bool) -> Result<(), String> {
+    let path = wiring_path(shared);
+    let content = read_config(&path)?.unwrap_or_default();
+    let fpath = fpath_line(shared);
+    let names = function_names(&get_autoload_dir(shared));
+
+    let mut lines: Vec<String> = content.lines().map(|line| line.to_string()).collect();
+    let existing = lines.iter().position(|line| line.trim() == fpath);
+
+    let new_lines = match existing {
+        Some(index) => {
+            let end = match lines.get(index + 1) {
+                Some(next) if next.trim_start().starts_with("autoload -Uz") => index + 2,
+                _ => index + 1,
+            };
+            let mut block = Vec::new();
+            if !names.is_empty() {
+                block.push(fpath);
+                block.push(format!("autoload -Uz {}", names.join(" ")));
+            } else if index > 0 && lines[index - 1].starts_with("# Autoloaded functions") {
+                // Drop the comment and the blank line before it as well
+                let start = if index > 1 && lines[index - 2].trim().is_empty() { index - 2 } else { index - 1 };
+                lines.drain(start..end);
+                return write_config(&path, &(lines.join("\n") + "\n"));
+            }
+            lines.splice(index..end, block);
+            lines
+        }
+        None if names.is_empty() => return Ok(()),
+        None => {
+            if lines.last().map(|line| !line.trim().is_empty()).unwrap_or(false) {
+                lines.push(String::new());
+            }
+            let dir = if shared { "~/.zsh/functions" } else { "~/.zsh/functions.local" };
+            lines.push(format!("# Autoloaded functions (one file per function in {})", dir));
+            lines.push(fpath);
+            lines.push(format!("autoload -Uz {}", names.join(" ")));
+            lines
+        }
+    };
+
+    write_config(&path, &(new_lines.join("\n") + "\n"))
+}
+
+fn read_function(dir: &Path, name: &str, shared: bool) -> Result<AutoloadFunction, String> {
+    validate_name(name)?;
+    let path = dir.join(name);
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read function '{}': {}", name, e))?;
+
+    Ok(AutoloadFunction {
+        name: name.to_string(),
+        content: content.trim_end().to_string(),
+        shared,
+        file: relative_to_home(&path),
+    })
+}
+
+fn write_function(name: &str, content: &str, shared: bool) -> Result<(), String> {
+    if let Some(error) = check_zsh_syntax(content) {
+        return Err(format!("Function has a syntax error: {}", error));
+    }
+
+    let dir = get_autoload_dir(shared);
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create functions directory: {}", e))?;
+    write_config(&dir.join(name), &format!("{}\n", content.trim_end()))?;
+    sync_wiring(shared)
+}
+
+#[tauri::command]
+pub fn list_autoload_functions(shared: bool) -> Result<Vec<AutoloadFunction>, String> {
+    let dir = get_autoload_dir(shared);
+    function_names(&dir)
+        .iter()
+        .map(|name| read_function(&dir, name, shared))
+        .collect()
+}
+
+/// Create `name` holding the function body `content`
+#[tauri::command]
+pub fn add_autoload_function(name: String, content: String, shared: bool) -> Result<AutoloadFunction, String> {
+    validate_name(&name)?;
+
+    let dir = get_autoload_dir(shared);
+    if dir.join(&name).exists() {
+        return Err(format!("Function '{}' already exists", name));
+    }
+    if list_functions(shared)?.iter().any(|f| f.name == name) {
+        return Err(format!("Function '{}' is already defined inline", name));
+    }
+
+    write_function(&name, &content, shared)?;
+    read_function(&dir, &name, shared)
+}
+
+#[tauri::command]
+pub fn update_autoload_function(name: String, content: String, shared: bool) -> Result<AutoloadFunction, String> {
+    validate_name(&name)?;
+    let dir = get_autoload_dir(shared);
+    if !dir.join(&name).is_file() {
+        return Err(format!("Function '{}' not found", name));
+    }
+
+    write_function(&name, &content, shared)?;
+    read_function(&dir, &name, shared)
+}
+
+#[tauri::command]
+pub fn delete_autoload_function(name: String, shared: bool) -> Result<(), String> {
+    validate_name(&name)?;
+    let path = get_autoload_dir(shared).join(&name);
+    if !path.is_file() {
+        return Err(format!("Function '{}' not found", name));
+    }
+
+    fs::remove_file(&path).map_err(|e| format!("Failed to delete function '{}': {}", name, e))?;
+    sync_wiring(shared)
+}
+
+/// Inline bodies are stored indented by two spaces after their first line
+fn dedent(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| line.strip_prefix("  ").unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Move a function between functions(.local).zsh and its autoload directory.
+/// The new copy is written before the old one is removed.
+#[tauri::command]
+pub fn move_function(name: String, shared: bool, to_autoload: bool) -> Result<(), String> {
+    validate_name(&name)?;
+
+    if to_autoload {
+        let function = list_functions(shared)?
+            .into_iter()
+            .find(|f| f.name == name)
+            .ok_or_else(|| format!("Function '{}' not found", name))?;
+        if get_autoload_dir(shared).join(&name).exists() {
+            return Err(format!("Function '{}' already exists", name));
+        }
+
+        write_function(&name, &dedent(&function.content), shared)?;
+        delete_function(name, shared)
+    } else {
+        let function = read_function(&get_autoload_dir(shared), &name, shared)?;
+        if list_functions(shared)?.iter().any(|f| f.name == name) {
+            return Err(format!("Function '{}' is already defined inline", name));
+        }
+
+        add_function(name.clone(), function.content, shared)?;
+        delete_autoload_function(name, shared)
+    }
+}
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::git::{get_zsh_config_dir, run_git_command};
+use super::progress::{run_git_streaming, GitTask};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitBranch {
+    pub name: String,
+    pub current: bool,
+    pub remote: bool,
+    pub upstream: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitRemote {
+    pub name: String,
+    pub url: String,
+}
+
+/// Reject names git would parse as options
+fn check_argument(kind: &str, value: &str) -> Result<(), String> {
+    if value.trim().is_empty() || value.starts_with('-') {
+        return Err(format!("Invalid {} name '{}'", kind, value));
+    }
+    Ok(())
+}
+
+fn check_branch_name(name: &str) -> Result<(), String> {
+    check_argument("branch", name)?;
+    run_git_command(&["check-ref-format", "--branch", name])
+        .map(|_| ())
+        .map_err(|_| format!("Invalid branch name '{}'", name))
+}
+
+/// Upstream of the current branch (e.g. "origin/main"), if one is configured
+pub(crate) fn current_upstream() -> Option<String> {
+    run_git_command(&["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{upstream}"])
+        .ok()
+        .map(|output| output.trim().to_string())
+        .filter(|upstream| !upstream.is_empty())
+}
+
+fn current_branch() -> Result<String, String> {
+    let output = run_git_command(&["symbolic-ref", "--short", "HEAD"])
+        .map_err(|_| "Not on a branch (detached HEAD)".to_string())?;
+    Ok(output.trim().to_string())
+}
+
+#[tauri::command]
+pub fn git_branches() -> Result<Vec<GitBranch>, String> {
+    let format = "%(HEAD)%00%(refname)%00%(refname:short)%00%(upstream:short)";
+    let output = run_git_command(&["for-each-ref", &format!("--format={}", format), "refs/heads", "refs/remotes"])?;
+
+    let branches = output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\0').collect();
+            if fields.len() < 4 {
+                return None;
+            }
+
+            // Skip symbolic refs such as origin/HEAD
+            if fields[1].starts_with("refs/remotes/") && fields[1].ends_with("/HEAD") {
+                return None;
+            }
+
+            Some(GitBranch {
+                name: fields[2].to_string(),
+                current: fields[0] == "*",
+                remote: fields[1].starts_with("refs/remotes/"),
+                upstream: Some(fields[3].to_string()).filter(|u| !u.is_empty()),
+            })
+        })
+        .collect();
+
+    Ok(branches)
+}
+
+#[tauri::command]
+pub fn git_create_branch(name: String, checkout: bool) -> Result<String, String> {
+    check_branch_name(&name)?;
+
+    let output = if checkout {
+        run_git_command(&["switch", "-c", &name])?
+    } else {
+        run_git_command(&["branch", &name])?
+    };
+
+    Ok(output.trim().to_string())
+}
+
+#[tauri::command]
+pub fn git_switch_branch(name: String) -> Result<String, String> {
+    check_argument("branch", &name)?;
+
+    // `git switch` creates a tracking branch when only a remote branch of that name exists
+    let output = run_git_command(&["switch", &name])?;
+    Ok(output.trim().to_string())
+}
+
+#[tauri::command]
+pub fn git_remotes() -> Result<Vec<GitRemote>, String> {
+    let output = run_git_command(&["remote"])?;
+
+    let remotes = output
+        .lines()
+        .filter(|name| !name.trim().is_empty())
+        .map(|name| {
+            let url = run_git_command(&["remote", "get-url", name])
+                .map(|url| url.trim().to_string())
+                .unwrap_or_default();
+            GitRemote {
+                name: name.trim().to_string(),
+                url,
+            }
+        })
+        .collect();
+
+    Ok(remotes)
+}
+
+#[tauri::command]
+pub fn git_add_remote(name: String, url: String) -> Result<(), String> {
+    check_argument("remote", &name)?;
+    check_argument("remote URL", &url)?;
+
+    run_git_command(&["remote", "add", &name, &url])?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn git_remove_remote(name: String) -> Result<(), String> {
+    check_argument("remote", &name)?;
+
+    run_git_command(&["remote", "remove", &name])?;
+    Ok(())
+}
+
+/// Track `remote/branch` from the current branch.
+/// When the remote branch does not exist yet (e.g. a freshly created remote), it is pushed first.
+#[tauri::command]
+pub async fn git_set_upstream(
+    app: AppHandle,
+    remote: String,
+    branch: Option<String>,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    check_argument("remote", &remote)?;
+
+    let local = current_branch()?;
+    let branch = branch.unwrap_or_else(|| local.clone());
+    check_branch_name(&branch)?;
+
+    // Both network steps report under the same operation id, which ends once both are done
+    let mut task = GitTask::new(&app, operation_id);
+    let result = set_upstream(&mut task, &remote, &local, &branch).await;
+    task.finish(result)
+}
+
+async fn set_upstream(task: &mut GitTask, remote: &str, local: &str, branch: &str) -> Result<String, String> {
+    task.run(get_zsh_config_dir(), &["fetch", "--progress", remote], None).await?;
+
+    let remote_ref = format!("refs/remotes/{}/{}", remote, branch);
+    let output = if run_git_command(&["rev-parse", "--verify", "--quiet", &remote_ref]).is_ok() {
+        run_git_command(&["branch", &format!("--set-upstream-to={}/{}", remote, branch)])?
+    } else {
+        let refspec = format!("{}:{}", local, branch);
+        let args = ["push", "--progress", "--set-upstream", remote, &refspec];
+        task.run(get_zsh_config_dir(), &args, None).await?
+    };
+
+    Ok(output.trim().to_string())
+}
+
+/// Fetch without merging, from one remote or all of them
+#[tauri::command]
+pub async fn git_fetch(
+    app: AppHandle,
+    remote: Option<String>,
+    operation_id: Option<String>,
+    timeout_secs: Option<u64>,
+) -> Result<String, String> {
+    let mut args = vec!["fetch", "--prune", "--progress"];
+    match remote.as_deref() {
+        Some(remote) => {
+            check_argument("remote", remote)?;
+            args.push(remote);
+        }
+        None => args.push("--all"),
+    }
+
+    let output = run_git_streaming(&app, operation_id, get_zsh_config_dir(), &args, timeout_secs).await?;
+    Ok(output.trim().to_string())
+}
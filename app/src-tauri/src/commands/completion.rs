@@ -0,0 +1,529 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use regex::Regex;
+
+use super::alias::list_aliases;
+use super::function::list_functions;
+use super::git::get_zsh_config_dir;
+use super::hooks::check_zsh_syntax;
+use super::plugin_managers::oh_my_zsh::OhMyZshBackend;
+use super::plugin_managers::{config_files, get_home_dir, get_oh_my_zsh_dir, read_config, relative_to_home, write_config, PluginBackend};
+
+const FPATH_LINE: &str = r#"fpath=("$HOME/.zsh/completions" $fpath)"#;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FpathDir {
+    pub path: String,
+    /// File adding the directory relative to the home directory, "oh-my-zsh" or "zsh" for defaults
+    pub source: String,
+    pub exists: bool,
+    /// ~/.zsh/completions, where `add_completion_file` writes
+    pub managed: bool,
+    pub completions: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompletionFunction {
+    /// Function name, e.g. "_git"
+    pub name: String,
+    pub path: String,
+    /// Commands from the `#compdef` header
+    pub commands: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Compdef {
+    pub command: String,
+    /// Completion function such as "_git", or the command whose completion is reused (`compdef g=git`)
+    pub completion: String,
+    /// Written as `command=service`
+    pub service: bool,
+    /// File relative to the home directory, and 1-based line
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompdumpRebuild {
+    pub removed: Vec<String>,
+    /// Whether a fresh ~/.zcompdump was generated; otherwise the next shell does it
+    pub regenerated: bool,
+}
+
+pub(crate) fn get_completions_dir() -> PathBuf {
+    get_zsh_config_dir().join("completions")
+}
+
+fn get_config_path() -> PathBuf {
+    get_zsh_config_dir().join("config.zsh")
+}
+
+/// Expand `~`, `$HOME` and the oh-my-zsh variables; other parameters are left as written
+fn expand_path(word: &str) -> String {
+    let home = get_home_dir().to_string_lossy().to_string();
+    let zsh = get_oh_my_zsh_dir().to_string_lossy().to_string();
+    let word = word.trim_matches(|c| c == '"' || c == '\'');
+
+    let word = match word.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("{}{}", home, rest),
+        _ => word.to_string(),
+    };
+
+    word.replace("${ZSH_CUSTOM}", &format!("{}/custom", zsh))
+        .replace("$ZSH_CUSTOM", &format!("{}/custom", zsh))
+        .replace("${ZSH}", &zsh)
+        .replace("$ZSH", &zsh)
+        .replace("${HOME}", &home)
+        .replace("$HOME", &home)
+}
+
+/// Directories added by `fpath=(dir $fpath)`, `fpath+=(dir)` or `fpath+=dir` lines
+pub(crate) fn parse_fpath_dirs(content: &str) -> Vec<String> {
+    let re = Regex::new(r"^\s*(?:export\s+)?fpath\+?=\(?([^)#]*)\)?").unwrap();
+
+    content
+        .lines()
+        .filter_map(|line| re.captures(line))
+        .flat_map(|captures| {
+            captures[1]
+                .split_whitespace()
+                .filter(|word| !word.contains("fpath"))
+                .map(expand_path)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// `compdef _git g gco` and `compdef g=git` lines; `compdef -d` deletions are skipped
+pub(crate) fn parse_compdefs(content: &str) -> Vec<(usize, String, String, bool)> {
+    let mut compdefs = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        let Some(rest) = trimmed.strip_prefix("compdef ") else {
+            continue;
+        };
+        let words: Vec<&str> = rest
+            .split('#')
+            .next()
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter(|word| *word != "&&" && *word != ";")
+            .collect();
+        if words.contains(&"-d") {
+            continue;
+        }
+        let words: Vec<&str> = words.into_iter().filter(|word| !word.starts_with('-')).collect();
+
+        if words.first().map(|word| word.contains('=')).unwrap_or(false) {
+            for word in words {
+                if let Some((command, service)) = word.split_once('=') {
+                    compdefs.push((index + 1, command.to_string(), service.to_string(), true));
+                }
+            }
+        } else if let Some((function, commands)) = words.split_first() {
+            for command in commands {
+                compdefs.push((index + 1, command.to_string(), function.to_string(), false));
+            }
+        }
+    }
+
+    compdefs
+}
+
+/// Commands listed after `#compdef` on the first line of a completion file
+fn compdef_header(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| {
+            content
+                .lines()
+                .next()
+                .and_then(|line| line.strip_prefix("#compdef"))
+                .map(|rest| {
+                    rest.split_whitespace()
+                        .filter(|word| !word.starts_with('-'))
+                        .map(|word| word.split('=').next().unwrap_or(word).to_string())
+                        .collect()
+                })
+        })
+        .unwrap_or_default()
+}
+
+fn completion_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file()
+                        && path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().starts_with('_'))
+                            .unwrap_or(false)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// The default fpath of zsh, when it is installed
+fn system_fpath() -> Vec<String> {
+    Command::new("zsh")
+        .args(["-f", "-c", "print -l -- $fpath"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).lines().map(|line| line.to_string()).collect())
+        .unwrap_or_default()
+}
+
+fn fpath_dirs() -> Vec<(String, String)> {
+    let mut dirs: Vec<(String, String)> = Vec::new();
+    let mut push = |path: String, source: &str| {
+        if !dirs.iter().any(|(existing, _)| *existing == path) {
+            dirs.push((path, source.to_string()));
+        }
+    };
+
+    for file in config_files() {
+        if let Ok(Some(content)) = read_config(&file) {
+            for dir in parse_fpath_dirs(&content) {
+                push(dir, &relative_to_home(&file));
+            }
+        }
+    }
+
+    // oh-my-zsh adds its own directories and those of enabled plugins
+    if OhMyZshBackend.detect() {
+        let zsh = get_oh_my_zsh_dir();
+        for plugin in OhMyZshBackend.list().unwrap_or_default() {
+            for base in [zsh.join("custom/plugins"), zsh.join("plugins")] {
+                let dir = base.join(&plugin.name);
+                if dir.exists() {
+                    push(dir.to_string_lossy().to_string(), "oh-my-zsh");
+                    break;
+                }
+            }
+        }
+        push(zsh.join("functions").to_string_lossy().to_string(), "oh-my-zsh");
+        push(zsh.join("completions").to_string_lossy().to_string(), "oh-my-zsh");
+    }
+
+    for dir in system_fpath() {
+        push(dir, "zsh");
+    }
+
+    dirs
+}
+
+/// Directories on fpath, from the managed config first, then oh-my-zsh and zsh defaults
+#[tauri::command]
+pub fn list_fpath() -> Result<Vec<FpathDir>, String> {
+    let managed = get_completions_dir();
+
+    Ok(fpath_dirs()
+        .into_iter()
+        .map(|(path, source)| {
+            let dir = PathBuf::from(&path);
+            FpathDir {
+                exists: dir.is_dir(),
+                managed: dir == managed,
+                completions: completion_files(&dir).len(),
+                path,
+                source,
+            }
+        })
+        .collect())
+}
+
+/// Completion functions (`_*` files) on fpath; the first directory defining a name wins, as in zsh
+#[tauri::command]
+pub fn list_completions() -> Result<Vec<CompletionFunction>, String> {
+    let mut completions: Vec<CompletionFunction> = Vec::new();
+
+    for (dir, _) in fpath_dirs() {
+        for path in completion_files(Path::new(&dir)) {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            if completions.iter().any(|completion| completion.name == name) {
+                continue;
+            }
+            completions.push(CompletionFunction {
+                commands: compdef_header(&path),
+                path: path.to_string_lossy().to_string(),
+                name,
+            });
+        }
+    }
+
+    completions.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(completions)
+}
+
+/// `compdef` lines in the managed config
+#[tauri::command]
+pub fn list_compdefs() -> Result<Vec<Compdef>, String> {
+    let mut compdefs = Vec::new();
+
+    for file in config_files() {
+        let Some(content) = read_config(&file)? else {
+            continue;
+        };
+        for (line, command, completion, service) in parse_compdefs(&content) {
+            compdefs.push(Compdef {
+                command,
+                completion,
+                service,
+                file: relative_to_home(&file),
+                line,
+            });
+        }
+    }
+
+    Ok(compdefs)
+}
+
+/// Put ~/.zsh/completions on fpath in config.zsh, before its compinit
+fn ensure_completions_on_fpath() -> Result<(), String> {
+    let managed = get_completions_dir().to_string_lossy().to_string();
+    let listed = config_files()
+        .iter()
+        .filter_map(|file| read_config(file).ok().flatten())
+        .any(|content| parse_fpath_dirs(&content).contains(&managed));
+    if listed {
+        return Ok(());
+    }
+
+    let path = get_config_path();
+    let content = read_config(&path)?.unwrap_or_default();
+    let mut lines: Vec<String> = content.lines().map(|line| line.to_string()).collect();
+    let mut index = lines
+        .iter()
+        .position(|line| !line.trim_start().starts_with('#') && line.contains("compinit"))
+        .unwrap_or(lines.len());
+    // Above the comments describing compinit
+    while index > 0 && lines[index - 1].trim_start().starts_with('#') {
+        index -= 1;
+    }
+
+    lines.splice(index..index, [
+        "# Custom completions (_mytool files)".to_string(),
+        FPATH_LINE.to_string(),
+        String::new(),
+    ]);
+    write_config(&path, &(lines.join("\n") + "\n"))
+}
+
+/// Write a completion function to ~/.zsh/completions and put that directory on fpath.
+/// `name` gets a leading underscore when it has none.
+#[tauri::command]
+pub fn add_completion_file(name: String, content: String, overwrite: bool) -> Result<CompletionFunction, String> {
+    let name = if name.starts_with('_') { name } else { format!("_{}", name) };
+    if name.len() < 2 || !name.chars().all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c)) {
+        return Err(format!("Invalid completion name '{}'", name));
+    }
+
+    if let Some(error) = check_zsh_syntax(&content) {
+        return Err(format!("Completion has a syntax error: {}", error));
+    }
+
+    let dir = get_completions_dir();
+    let path = dir.join(&name);
+    if path.exists() && !overwrite {
+        return Err(format!("Completion '{}' already exists", name));
+    }
+
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create completions directory: {}", e))?;
+    let content = if content.ends_with('\n') { content } else { content + "\n" };
+    write_config(&path, &content)?;
+    ensure_completions_on_fpath()?;
+
+    Ok(CompletionFunction {
+        commands: compdef_header(&path),
+        path: path.to_string_lossy().to_string(),
+        name,
+    })
+}
+
+/// Use an existing completion for an alias or function: `compdef _git g` for a completion
+/// function, `compdef g=git` to reuse another command's completion. Written to config.zsh
+/// after compinit, replacing an earlier compdef for the same name there.
+#[tauri::command]
+pub fn add_compdef(command: String, completion: String) -> Result<Compdef, String> {
+    let defined = list_aliases(true)?
+        .into_iter()
+        .chain(list_aliases(false)?)
+        .map(|alias| alias.name)
+        .chain(list_functions(true)?.into_iter().chain(list_functions(false)?).map(|function| function.name))
+        .any(|name| name == command);
+    if !defined {
+        return Err(format!("'{}' is not one of your aliases or functions", command));
+    }
+
+    let completions = list_completions()?;
+    let service = !completion.starts_with('_');
+    let known = if service {
+        completions.iter().any(|c| c.commands.contains(&completion))
+    } else {
+        completions.iter().any(|c| c.name == completion)
+    };
+    if !known {
+        return Err(format!("No completion found for '{}'", completion));
+    }
+
+    let line = if service {
+        format!("compdef {}={}", command, completion)
+    } else {
+        format!("compdef {} {}", completion, command)
+    };
+
+    let path = get_config_path();
+    let content = read_config(&path)?.unwrap_or_default();
+    let content = remove_compdef_lines(&content, &command);
+    let mut lines: Vec<String> = content.lines().map(|line| line.to_string()).collect();
+
+    // After the last compdef, otherwise right after compinit, otherwise at the end
+    let index = lines
+        .iter()
+        .rposition(|line| line.trim_start().starts_with("compdef "))
+        .or_else(|| {
+            lines
+                .iter()
+                .position(|line| !line.trim_start().starts_with('#') && line.contains("compinit"))
+        })
+        .map(|index| index + 1)
+        .unwrap_or(lines.len());
+    lines.insert(index, line);
+
+    write_config(&path, &(lines.join("\n") + "\n"))?;
+
+    Ok(Compdef {
+        command,
+        completion,
+        service,
+        file: relative_to_home(&path),
+        line: index + 1,
+    })
+}
+
+/// Byte ranges of the words of a line, up to a comment
+fn word_ranges(line: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (index, c) in line.char_indices() {
+        if c.is_whitespace() {
+            if let Some(begin) = start.take() {
+                ranges.push((begin, index));
+            }
+        } else if c == '#' && start.is_none() {
+            return ranges;
+        } else {
+            start.get_or_insert(index);
+        }
+    }
+    if let Some(begin) = start {
+        ranges.push((begin, line.len()));
+    }
+
+    ranges
+}
+
+/// Drop `command` from compdef lines, removing lines left without commands.
+/// Only the matching word is cut, so flags such as `-P` and comments stay.
+fn remove_compdef_lines(content: &str, command: &str) -> String {
+    let mut lines = Vec::new();
+
+    for line in content.lines() {
+        let declared = parse_compdefs(line);
+        let matching = declared.iter().filter(|(_, c, _, _)| c == command).count();
+        if matching == 0 {
+            lines.push(line.to_string());
+            continue;
+        }
+        if matching == declared.len() {
+            continue;
+        }
+
+        // Arguments after `compdef`; in the `_function cmd...` form the first one is the function
+        let args: Vec<(usize, usize)> = word_ranges(line)
+            .into_iter()
+            .skip(1)
+            .filter(|(start, _)| !line[*start..].starts_with('-'))
+            .collect();
+        let service = declared[0].3;
+        let targets: Vec<&(usize, usize)> = args
+            .iter()
+            .skip(if service { 0 } else { 1 })
+            .filter(|(start, end)| {
+                let word = &line[*start..*end];
+                if service { word.split('=').next() == Some(command) } else { word == command }
+            })
+            .collect();
+
+        let mut rewritten = line.to_string();
+        for (start, end) in targets.into_iter().rev() {
+            let before = rewritten[..*start].trim_end_matches([' ', '\t']).len();
+            rewritten = format!("{}{}", &rewritten[..before], &rewritten[*end..]);
+        }
+        lines.push(rewritten);
+    }
+
+    let mut result = lines.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+#[tauri::command]
+pub fn remove_compdef(command: String) -> Result<(), String> {
+    let path = get_config_path();
+    let content = read_config(&path)?.unwrap_or_default();
+    let new_content = remove_compdef_lines(&content, &command);
+    if new_content == content {
+        return Err(format!("No compdef for '{}' in ~/.zsh/config.zsh", command));
+    }
+    write_config(&path, &new_content)
+}
+
+/// Delete ~/.zcompdump* (including oh-my-zsh's per-host dumps) and regenerate ~/.zcompdump
+/// with the managed fpath in a non-interactive zsh
+#[tauri::command]
+pub fn rebuild_completion_dump() -> Result<CompdumpRebuild, String> {
+    let home = get_home_dir();
+    let mut removed = Vec::new();
+
+    let entries = fs::read_dir(&home).map_err(|e| format!("Failed to read home directory: {}", e))?;
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with(".zcompdump") && entry.path().is_file() {
+            fs::remove_file(entry.path()).map_err(|e| format!("Failed to remove {}: {}", name, e))?;
+            removed.push(name);
+        }
+    }
+    removed.sort();
+
+    let dirs: Vec<String> = fpath_dirs()
+        .into_iter()
+        .map(|(path, _)| path)
+        .filter(|path| Path::new(path).is_dir())
+        .collect();
+    let script = "fpath=(\"$@\" $fpath); autoload -Uz compinit && compinit -i -d \"$HOME/.zcompdump\"";
+
+    let regenerated = Command::new("zsh")
+        .args(["-f", "-c", script, "zsh"])
+        .args(&dirs)
+        .env("HOME", &home)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+
+    Ok(CompdumpRebuild { removed, regenerated })
+}
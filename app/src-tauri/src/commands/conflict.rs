@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use super::git::{get_zsh_config_dir, run_git_command};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitOperation {
+    Rebase,
+    Merge,
+    Revert,
+}
+
+/// A conflicted file with the content of each side.
+/// A side is None when the file does not exist there (added or deleted).
+/// During a rebase "ours" is the upstream branch and "theirs" is the local commit being replayed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConflictFile {
+    pub path: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConflictState {
+    pub operation: Option<GitOperation>,
+    pub files: Vec<ConflictFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "strategy", content = "content", rename_all = "kebab-case")]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    Merged(String),
+}
+
+fn get_git_dir() -> Result<PathBuf, String> {
+    let output = run_git_command(&["rev-parse", "--absolute-git-dir"])?;
+    Ok(PathBuf::from(output.trim()))
+}
+
+/// Detect a rebase, merge or revert left in progress (e.g. by a conflicting `pull --rebase`)
+pub(crate) fn operation_in_progress() -> Option<GitOperation> {
+    let git_dir = get_git_dir().ok()?;
+
+    if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+        Some(GitOperation::Rebase)
+    } else if git_dir.join("MERGE_HEAD").exists() {
+        Some(GitOperation::Merge)
+    } else if git_dir.join("REVERT_HEAD").exists() {
+        Some(GitOperation::Revert)
+    } else {
+        None
+    }
+}
+
+pub(crate) fn list_conflicted_paths() -> Result<Vec<String>, String> {
+    let output = run_git_command(&["diff", "--name-only", "--diff-filter=U", "-z"])?;
+
+    Ok(output
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(|path| path.to_string())
+        .collect())
+}
+
+/// Read a file from an index stage (1 = base, 2 = ours, 3 = theirs)
+pub(crate) fn read_stage(path: &str, stage: u8) -> Option<String> {
+    run_git_command(&["show", &format!(":{}:{}", stage, path)]).ok()
+}
+
+fn mark_resolved(path: &str) -> Result<(), String> {
+    if get_zsh_config_dir().join(path).exists() {
+        run_git_command(&["add", "--", path])?;
+    } else {
+        run_git_command(&["rm", "--cached", "--quiet", "--", path])?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn git_conflicts() -> Result<ConflictState, String> {
+    let operation = operation_in_progress();
+
+    let files = list_conflicted_paths()?
+        .into_iter()
+        .map(|path| ConflictFile {
+            base: read_stage(&path, 1),
+            ours: read_stage(&path, 2),
+            theirs: read_stage(&path, 3),
+            path,
+        })
+        .collect();
+
+    Ok(ConflictState { operation, files })
+}
+
+#[tauri::command]
+pub fn git_resolve_conflict(path: String, resolution: ConflictResolution) -> Result<(), String> {
+    if !list_conflicted_paths()?.contains(&path) {
+        return Err(format!("'{}' is not in conflict", path));
+    }
+
+    let content = match resolution {
+        ConflictResolution::Ours => read_stage(&path, 2),
+        ConflictResolution::Theirs => read_stage(&path, 3),
+        ConflictResolution::Merged(content) => Some(content),
+    };
+
+    let file_path = get_zsh_config_dir().join(&path);
+
+    // A missing side means the file was deleted on that side
+    match content {
+        Some(content) => fs::write(&file_path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path, e))?,
+        None => {
+            if file_path.exists() {
+                fs::remove_file(&file_path)
+                    .map_err(|e| format!("Failed to remove {}: {}", path, e))?;
+            }
+        }
+    }
+
+    mark_resolved(&path)
+}
+
+#[tauri::command]
+pub fn git_continue() -> Result<String, String> {
+    let operation = operation_in_progress().ok_or("No rebase, merge or revert in progress")?;
+
+    let remaining = list_conflicted_paths()?;
+    if !remaining.is_empty() {
+        return Err(format!("Unresolved conflicts remain: {}", remaining.join(", ")));
+    }
+
+    // Keeping only the upstream side leaves the replayed commit empty, which git refuses to continue with
+    let empty = run_git_command(&["diff", "--cached", "--quiet"]).is_ok();
+
+    // core.editor=true keeps the existing commit messages without opening an editor
+    let result = match operation {
+        GitOperation::Rebase if empty => run_git_command(&["rebase", "--skip"]),
+        GitOperation::Rebase => run_git_command(&["-c", "core.editor=true", "rebase", "--continue"]),
+        GitOperation::Merge => run_git_command(&["commit", "--no-edit"]),
+        GitOperation::Revert if empty => run_git_command(&["revert", "--skip"]),
+        GitOperation::Revert => run_git_command(&["-c", "core.editor=true", "revert", "--continue"]),
+    };
+
+    match result {
+        Ok(output) => Ok(output.trim().to_string()),
+        // The next commit of the rebase may conflict as well
+        Err(_) if !list_conflicted_paths()?.is_empty() => {
+            Err("The next commit has conflicts; resolve them and continue again".to_string())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[tauri::command]
+pub fn git_abort() -> Result<String, String> {
+    let output = match operation_in_progress() {
+        Some(GitOperation::Rebase) => run_git_command(&["rebase", "--abort"])?,
+        Some(GitOperation::Merge) => run_git_command(&["merge", "--abort"])?,
+        Some(GitOperation::Revert) => run_git_command(&["revert", "--abort"])?,
+        None => return Err("No rebase, merge or revert in progress".to_string()),
+    };
+
+    Ok(output.trim().to_string())
+}
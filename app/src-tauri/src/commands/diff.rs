@@ -0,0 +1,373 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+use super::alias::parse_alias_line;
+use super::function::parse_functions;
+use super::git::{get_zsh_config_dir, run_git_command};
+use super::plugin::parse_plugins_from_file;
+use super::plugin_lock::LOCKFILE_NAME;
+
+/// Versioned files in ~/.zsh that are parsed for aliases, functions and plugins
+pub(crate) const PARSED_FILES: &[&str] = &["aliases.zsh", "functions.zsh", "config.zsh", "plugins.zsh"];
+
+/// Versioned directories in ~/.zsh holding one completion or autoloaded function per file
+pub(crate) const MANAGED_DIRS: &[&str] = &["completions", "functions"];
+
+/// Autoloaded functions, stored one per file with the body as content
+const AUTOLOAD_DIR: &str = "functions";
+
+/// Every versioned file in ~/.zsh: the parsed files, the files of the managed directories
+/// (including tracked ones deleted from the working tree) and the plugin lockfile
+pub(crate) fn managed_files() -> Vec<String> {
+    let zsh_dir = get_zsh_config_dir();
+    let mut files: Vec<String> = PARSED_FILES.iter().map(|file| file.to_string()).collect();
+    files.push(LOCKFILE_NAME.to_string());
+
+    for dir in MANAGED_DIRS {
+        let on_disk = fs::read_dir(zsh_dir.join(dir))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().is_file())
+                    .map(|entry| format!("{}/{}", dir, entry.file_name().to_string_lossy()))
+                    .collect()
+            })
+            .unwrap_or_else(|_| Vec::new());
+        files.extend(on_disk);
+    }
+
+    let mut args = vec!["ls-files", "-z", "--"];
+    args.extend(MANAGED_DIRS);
+    if let Ok(output) = run_git_command(&args) {
+        files.extend(output.split('\0').filter(|path| !path.is_empty()).map(|path| path.to_string()));
+    }
+
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Files under the managed directories in a commit, so files deleted since then are compared too
+fn managed_files_in(rev: &str) -> Vec<String> {
+    let mut args = vec!["ls-tree", "-r", "-z", "--name-only", rev, "--"];
+    args.extend(MANAGED_DIRS);
+    run_git_command(&args)
+        .map(|output| output.split('\0').filter(|path| !path.is_empty()).map(|path| path.to_string()).collect())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum EntryKind {
+    Alias,
+    Function,
+    Plugin,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SemanticChange {
+    pub file: String,
+    pub kind: EntryKind,
+    pub change: ChangeKind,
+    pub name: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub summary: String,
+}
+
+/// Aliases, functions and plugins defined in a single file.
+/// When a name is defined twice the last definition wins, as in zsh.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct ConfigEntries {
+    pub aliases: BTreeMap<String, String>,
+    pub functions: BTreeMap<String, String>,
+    pub plugins: Vec<String>,
+}
+
+/// One side of a comparison
+enum Source {
+    Empty,
+    Revision(String),
+    Index,
+    WorkTree,
+}
+
+pub(crate) fn parse_entries(content: &str) -> ConfigEntries {
+    let aliases = content
+        .lines()
+        .filter_map(parse_alias_line)
+        .map(|alias| (alias.name, alias.command))
+        .collect();
+
+    let functions = parse_functions(content)
+        .into_iter()
+        .map(|func| (func.name, func.content))
+        .collect();
+
+    ConfigEntries {
+        aliases,
+        functions,
+        plugins: parse_plugins_from_file(content),
+    }
+}
+
+/// Resolve a revision to a commit, or None if it does not exist (e.g. HEAD in an empty repo)
+fn resolve_revision(rev: &str) -> Option<String> {
+    run_git_command(&["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", rev)])
+        .ok()
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty())
+}
+
+fn read_source(source: &Source, file: &str) -> String {
+    match source {
+        Source::Empty => String::new(),
+        // A file missing from a commit or the index is treated as empty
+        Source::Revision(rev) => run_git_command(&["show", &format!("{}:{}", rev, file)]).unwrap_or_default(),
+        Source::Index => run_git_command(&["show", &format!(":{}", file)]).unwrap_or_default(),
+        Source::WorkTree => fs::read_to_string(get_zsh_config_dir().join(file)).unwrap_or_default(),
+    }
+}
+
+fn diff_definitions(
+    file: &str,
+    kind: EntryKind,
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+    changes: &mut Vec<SemanticChange>,
+) {
+    let label = match kind {
+        EntryKind::Alias => "alias",
+        EntryKind::Function => "function",
+        EntryKind::Plugin => "plugin",
+    };
+
+    let mut names: Vec<&String> = old.keys().chain(new.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    for name in names {
+        let (change, summary) = match (old.get(name), new.get(name)) {
+            (None, Some(_)) => (ChangeKind::Added, format!("{} `{}` added", label, name)),
+            (Some(_), None) => (ChangeKind::Removed, format!("{} `{}` removed", label, name)),
+            (Some(before), Some(after)) if before != after => {
+                let summary = if kind == EntryKind::Function {
+                    format!("function `{}` body modified", name)
+                } else {
+                    format!("{} `{}` changed from `{}` to `{}`", label, name, before, after)
+                };
+                (ChangeKind::Modified, summary)
+            }
+            _ => continue,
+        };
+
+        changes.push(SemanticChange {
+            file: file.to_string(),
+            kind: kind.clone(),
+            change,
+            name: name.clone(),
+            old_value: old.get(name).cloned(),
+            new_value: new.get(name).cloned(),
+            summary,
+        });
+    }
+}
+
+fn diff_plugins(file: &str, old: &[String], new: &[String], changes: &mut Vec<SemanticChange>) {
+    for name in new.iter().filter(|p| !old.contains(p)) {
+        changes.push(SemanticChange {
+            file: file.to_string(),
+            kind: EntryKind::Plugin,
+            change: ChangeKind::Added,
+            name: name.clone(),
+            old_value: None,
+            new_value: None,
+            summary: format!("plugin `{}` enabled", name),
+        });
+    }
+
+    for name in old.iter().filter(|p| !new.contains(p)) {
+        changes.push(SemanticChange {
+            file: file.to_string(),
+            kind: EntryKind::Plugin,
+            change: ChangeKind::Removed,
+            name: name.clone(),
+            old_value: None,
+            new_value: None,
+            summary: format!("plugin `{}` disabled", name),
+        });
+    }
+}
+
+pub(crate) fn diff_contents(file: &str, old_content: &str, new_content: &str) -> Vec<SemanticChange> {
+    let old = parse_entries(old_content);
+    let new = parse_entries(new_content);
+    let mut changes = Vec::new();
+
+    diff_definitions(file, EntryKind::Alias, &old.aliases, &new.aliases, &mut changes);
+    diff_definitions(file, EntryKind::Function, &old.functions, &new.functions, &mut changes);
+    diff_plugins(file, &old.plugins, &new.plugins, &mut changes);
+
+    changes
+}
+
+/// An autoloaded function file as a single function named after the file
+fn autoload_entries(file: &str, content: &str) -> ConfigEntries {
+    let mut entries = ConfigEntries::default();
+    if !content.is_empty() {
+        let name = file.rsplit('/').next().unwrap_or(file);
+        entries.functions.insert(name.to_string(), content.trim_end().to_string());
+    }
+    entries
+}
+
+fn diff_sources(old: &Source, new: &Source) -> Vec<SemanticChange> {
+    let mut files = managed_files();
+    for source in [old, new] {
+        if let Source::Revision(rev) = source {
+            files.extend(managed_files_in(rev));
+        }
+    }
+    files.sort();
+    files.dedup();
+
+    files
+        .iter()
+        .flat_map(|file| {
+            let old_content = read_source(old, file);
+            let new_content = read_source(new, file);
+            if old_content == new_content {
+                Vec::new()
+            } else if PARSED_FILES.contains(&file.as_str()) {
+                diff_contents(file, &old_content, &new_content)
+            } else if file.strip_prefix(AUTOLOAD_DIR).is_some_and(|rest| rest.starts_with('/')) {
+                let (old, new) = (autoload_entries(file, &old_content), autoload_entries(file, &new_content));
+                let mut changes = Vec::new();
+                diff_definitions(file, EntryKind::Function, &old.functions, &new.functions, &mut changes);
+                changes
+            } else {
+                // Completions and the lockfile have no entries to report
+                Vec::new()
+            }
+        })
+        .collect()
+}
+
+fn head_source() -> Source {
+    match resolve_revision("HEAD") {
+        Some(hash) => Source::Revision(hash),
+        None => Source::Empty,
+    }
+}
+
+/// Semantic changes between HEAD and the working tree (everything `git_commit` would record)
+pub(crate) fn working_tree_changes() -> Vec<SemanticChange> {
+    diff_sources(&head_source(), &Source::WorkTree)
+}
+
+/// Report alias, function and plugin changes instead of raw diff text.
+///
+/// - no arguments: HEAD against the working tree
+/// - `staged`: HEAD against the index
+/// - `from` only: that commit against the working tree
+/// - `from` and `to`: between two commits
+#[tauri::command]
+pub fn git_semantic_diff(from: Option<String>, to: Option<String>, staged: bool) -> Result<Vec<SemanticChange>, String> {
+    let revision = |rev: &str| {
+        resolve_revision(rev)
+            .map(Source::Revision)
+            .ok_or_else(|| format!("Unknown revision '{}'", rev))
+    };
+
+    let (old, new) = match (from, to) {
+        (Some(_), _) | (_, Some(_)) if staged => {
+            return Err("Cannot combine staged changes with a commit range".to_string());
+        }
+        (None, Some(_)) => return Err("A 'from' revision is required when 'to' is given".to_string()),
+        (Some(from), Some(to)) => (revision(&from)?, revision(&to)?),
+        (Some(from), None) => (revision(&from)?, Source::WorkTree),
+        (None, None) if staged => (head_source(), Source::Index),
+        (None, None) => (head_source(), Source::WorkTree),
+    };
+
+    Ok(diff_sources(&old, &new))
+}
+
+/// Maximum length of a suggested commit message subject
+const COMMIT_MESSAGE_MAX_LEN: usize = 72;
+
+/// Build "aliases: add dcu, dcd; functions: update kimi; plugins: enable fzf-tab".
+/// Names are replaced by counts when the full list would not fit, then the message is truncated.
+pub(crate) fn commit_message_from_changes(changes: &[SemanticChange]) -> String {
+    let groups = [
+        (EntryKind::Alias, "aliases", ["add", "update", "remove"]),
+        (EntryKind::Function, "functions", ["add", "update", "remove"]),
+        (EntryKind::Plugin, "plugins", ["enable", "update", "disable"]),
+    ];
+
+    let build = |with_names: bool| -> String {
+        groups
+            .iter()
+            .filter_map(|(kind, label, verbs)| {
+                let actions: Vec<String> = [ChangeKind::Added, ChangeKind::Modified, ChangeKind::Removed]
+                    .iter()
+                    .zip(verbs.iter())
+                    .filter_map(|(change, verb)| {
+                        // The same name can change in several files; keep its first mention
+                        let mut names: Vec<&str> = Vec::new();
+                        for c in changes.iter().filter(|c| &c.kind == kind && &c.change == change) {
+                            if !names.contains(&c.name.as_str()) {
+                                names.push(&c.name);
+                            }
+                        }
+                        if names.is_empty() {
+                            None
+                        } else if with_names {
+                            Some(format!("{} {}", verb, names.join(", ")))
+                        } else {
+                            Some(format!("{} {}", verb, names.len()))
+                        }
+                    })
+                    .collect();
+
+                if actions.is_empty() {
+                    None
+                } else {
+                    Some(format!("{}: {}", label, actions.join(", ")))
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("; ")
+    };
+
+    let mut message = build(true);
+    if message.chars().count() > COMMIT_MESSAGE_MAX_LEN {
+        message = build(false);
+    }
+    if message.chars().count() > COMMIT_MESSAGE_MAX_LEN {
+        message = message.chars().take(COMMIT_MESSAGE_MAX_LEN - 3).collect::<String>() + "...";
+    }
+
+    message
+}
+
+/// Suggest a commit message summarising the uncommitted alias, function and plugin changes
+#[tauri::command]
+pub fn suggest_commit_message() -> Result<String, String> {
+    let changes = working_tree_changes();
+
+    if changes.is_empty() {
+        return Ok("config: update zsh configuration".to_string());
+    }
+
+    Ok(commit_message_from_changes(&changes))
+}
@@ -22,7 +22,7 @@ fn get_function_file_path(shared: bool) -> PathBuf {
     }
 }
 
-fn parse_functions(content: &str) -> Vec<Function> {
+pub(crate) fn parse_functions(content: &str) -> Vec<Function> {
     let mut functions = Vec::new();
     let lines: Vec<&str> = content.lines().collect();
     let mut i = 0;
@@ -87,7 +87,7 @@ fn parse_functions(content: &str) -> Vec<Function> {
     functions
 }
 
-fn try_parse_function_start(line: &str) -> Option<String> {
+pub(crate) fn try_parse_function_start(line: &str) -> Option<String> {
     let line = line.trim();
 
     // Skip comments and empty lines
@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use tauri::AppHandle;
+
+use super::conflict::{list_conflicted_paths, operation_in_progress, GitOperation};
+use super::diff::suggest_commit_message;
+use super::gitignore::{ensure_gitignore_rules, find_policy_violations};
+use super::progress::run_git_streaming;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GitStatus {
@@ -10,6 +16,21 @@ pub struct GitStatus {
     pub behind: u32,
     pub modified: Vec<String>,
     pub untracked: Vec<String>,
+    /// Paths with changes in the index
+    pub staged: Vec<String>,
+    /// Paths with changes in the working tree that are not staged
+    pub unstaged: Vec<String>,
+    pub conflicted: Vec<String>,
+    pub renamed: Vec<GitRename>,
+    pub upstream: Option<String>,
+    pub operation: Option<GitOperation>,
+    pub policy_violations: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitRename {
+    pub from: String,
+    pub to: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -20,97 +41,192 @@ pub struct GitCommit {
     pub date: String,
 }
 
-fn get_zsh_config_dir() -> PathBuf {
+pub(crate) fn get_zsh_config_dir() -> PathBuf {
     let home = dirs::home_dir().expect("Could not find home directory");
     home.join(".zsh")
 }
 
-fn run_git_command(args: &[&str]) -> Result<String, String> {
-    let config_dir = get_zsh_config_dir();
+pub(crate) fn run_git_command(args: &[&str]) -> Result<String, String> {
+    run_git_command_in(&get_zsh_config_dir(), args)
+}
 
+pub(crate) fn run_git_command_in(dir: &Path, args: &[&str]) -> Result<String, String> {
     let output = Command::new("git")
         .args(args)
-        .current_dir(&config_dir)
+        .current_dir(dir)
         .output()
         .map_err(|e| format!("Failed to execute git command: {}", e))?;
 
     if !output.status.success() {
+        // Some failures (e.g. "nothing to commit") are only reported on stdout
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Git command failed: {}", stderr));
+        let message = if stderr.trim().is_empty() {
+            String::from_utf8_lossy(&output.stdout)
+        } else {
+            stderr
+        };
+        return Err(format!("Git command failed: {}", message));
     }
 
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-#[tauri::command]
-pub fn git_status() -> Result<GitStatus, String> {
-    // Get current branch
-    let branch_output = run_git_command(&["rev-parse", "--abbrev-ref", "HEAD"])?;
-    let branch = branch_output.trim().to_string();
+/// Parsed output of `git status --porcelain=v2 --branch -z`
+#[derive(Debug, Default)]
+pub(crate) struct PorcelainStatus {
+    pub branch: String,
+    pub upstream: Option<String>,
+    pub ahead: u32,
+    pub behind: u32,
+    /// Every changed path that is tracked or staged, in git's order
+    pub changed: Vec<String>,
+    pub staged: Vec<String>,
+    pub unstaged: Vec<String>,
+    pub conflicted: Vec<String>,
+    pub untracked: Vec<String>,
+    pub renamed: Vec<GitRename>,
+}
+
+/// Record the index and working tree state of one entry from its `XY` field
+fn record_change(status: &mut PorcelainStatus, xy: &str, path: &str) {
+    let mut codes = xy.chars();
+    let index = codes.next().unwrap_or('.');
+    let worktree = codes.next().unwrap_or('.');
 
-    // Get modified files
-    let status_output = run_git_command(&["status", "--porcelain"])?;
-    let mut modified = Vec::new();
-    let mut untracked = Vec::new();
+    if index != '.' {
+        status.staged.push(path.to_string());
+    }
+    if worktree != '.' {
+        status.unstaged.push(path.to_string());
+    }
+    status.changed.push(path.to_string());
+}
 
-    for line in status_output.lines() {
-        if line.is_empty() {
+/// Parse porcelain v2 records. With `-z` paths are never quoted and a rename record is
+/// followed by a separate record holding the original path.
+pub(crate) fn parse_porcelain_v2(output: &str) -> PorcelainStatus {
+    let mut status = PorcelainStatus::default();
+    let mut records = output.split('\0').filter(|record| !record.is_empty());
+
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                // Keep the name `rev-parse --abbrev-ref HEAD` reports for a detached HEAD
+                "branch.head" if value == "(detached)" => status.branch = "HEAD".to_string(),
+                "branch.head" => status.branch = value.to_string(),
+                "branch.upstream" => status.upstream = Some(value.to_string()),
+                "branch.ab" => {
+                    for count in value.split_whitespace() {
+                        if let Some(ahead) = count.strip_prefix('+') {
+                            status.ahead = ahead.parse().unwrap_or(0);
+                        } else if let Some(behind) = count.strip_prefix('-') {
+                            status.behind = behind.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
             continue;
         }
 
-        let status_code = &line[..2];
-        let file = line[3..].trim().to_string();
+        let fields: Vec<&str> = match record.chars().next() {
+            Some('1') => record.splitn(9, ' ').collect(),
+            Some('2') => record.splitn(10, ' ').collect(),
+            Some('u') => record.splitn(11, ' ').collect(),
+            Some('?') => {
+                status.untracked.push(record[2..].to_string());
+                continue;
+            }
+            // Ignored files ('!') and anything unknown
+            _ => continue,
+        };
+        let Some(path) = fields.last().filter(|_| fields.len() > 2) else {
+            continue;
+        };
 
-        if status_code.starts_with("??") {
-            untracked.push(file);
-        } else if !status_code.trim().is_empty() {
-            modified.push(file);
+        match fields[0] {
+            "1" => record_change(&mut status, fields[1], path),
+            "2" => {
+                record_change(&mut status, fields[1], path);
+                if let Some(from) = records.next() {
+                    status.renamed.push(GitRename {
+                        from: from.to_string(),
+                        to: path.to_string(),
+                    });
+                }
+            }
+            _ => {
+                status.conflicted.push(path.to_string());
+                status.changed.push(path.to_string());
+            }
         }
     }
 
-    let clean = modified.is_empty() && untracked.is_empty();
-
-    // Get ahead/behind status
-    let mut ahead = 0;
-    let mut behind = 0;
-
-    if let Ok(upstream_output) = run_git_command(&["rev-parse", "--abbrev-ref", "@{upstream}"]) {
-        let upstream = upstream_output.trim();
-
-        if let Ok(ahead_output) = run_git_command(&["rev-list", "--count", &format!("{}..HEAD", upstream)]) {
-            ahead = ahead_output.trim().parse().unwrap_or(0);
-        }
+    status
+}
 
-        if let Ok(behind_output) = run_git_command(&["rev-list", "--count", &format!("HEAD..{}", upstream)]) {
-            behind = behind_output.trim().parse().unwrap_or(0);
-        }
-    }
+#[tauri::command]
+pub fn git_status() -> Result<GitStatus, String> {
+    let output = run_git_command(&["status", "--porcelain=v2", "--branch", "-z"])?;
+    let status = parse_porcelain_v2(&output);
 
     Ok(GitStatus {
-        branch,
-        clean,
-        ahead,
-        behind,
-        modified,
-        untracked,
+        clean: status.changed.is_empty() && status.untracked.is_empty(),
+        branch: status.branch,
+        ahead: status.ahead,
+        behind: status.behind,
+        modified: status.changed,
+        untracked: status.untracked,
+        staged: status.staged,
+        unstaged: status.unstaged,
+        conflicted: status.conflicted,
+        renamed: status.renamed,
+        upstream: status.upstream,
+        operation: operation_in_progress(),
+        policy_violations: find_policy_violations().unwrap_or_default(),
     })
 }
 
 #[tauri::command]
-pub fn git_pull() -> Result<String, String> {
-    let output = run_git_command(&["pull", "--rebase"])?;
-    Ok(output.trim().to_string())
+pub async fn git_pull(app: AppHandle, operation_id: Option<String>, timeout_secs: Option<u64>) -> Result<String, String> {
+    let args = ["pull", "--rebase", "--progress"];
+    match run_git_streaming(&app, operation_id, get_zsh_config_dir(), &args, timeout_secs).await {
+        Ok(output) => Ok(output.trim().to_string()),
+        Err(e) => {
+            // A conflicting rebase stops half-way; report it instead of the raw git error
+            if operation_in_progress().is_some() {
+                let conflicted = list_conflicted_paths().unwrap_or_default();
+                if !conflicted.is_empty() {
+                    return Err(format!(
+                        "Pull stopped with conflicts in: {}. Resolve them, then continue or abort the rebase.",
+                        conflicted.join(", ")
+                    ));
+                }
+            }
+            Err(e)
+        }
+    }
 }
 
 #[tauri::command]
-pub fn git_push() -> Result<String, String> {
-    let output = run_git_command(&["push"])?;
+pub async fn git_push(app: AppHandle, operation_id: Option<String>, timeout_secs: Option<u64>) -> Result<String, String> {
+    let args = ["push", "--progress"];
+    let output = run_git_streaming(&app, operation_id, get_zsh_config_dir(), &args, timeout_secs).await?;
     Ok(output.trim().to_string())
 }
 
 #[tauri::command]
 pub fn git_commit(message: String) -> Result<String, String> {
-    // Stage all changes
+    // Fall back to a message generated from the parsed changes
+    let message = if message.trim().is_empty() {
+        suggest_commit_message()?
+    } else {
+        message
+    };
+
+    // Keep local and secret files out of the commit, then stage all changes
+    ensure_gitignore_rules()?;
     run_git_command(&["add", "-A"])?;
 
     // Commit
@@ -118,12 +234,11 @@ pub fn git_commit(message: String) -> Result<String, String> {
     Ok(output.trim().to_string())
 }
 
-#[tauri::command]
-pub fn git_log(limit: usize) -> Result<Vec<GitCommit>, String> {
-    let format = "%H%n%s%n%an%n%ai%n---";
-    let output = run_git_command(&["log", &format!("-{}", limit), &format!("--format={}", format)])?;
+/// `git log --format` producing the fields of `GitCommit`, parsed by `parse_commits`
+pub(crate) const LOG_FORMAT: &str = "%H%n%s%n%an%n%ai%n---";
 
-    let commits: Vec<GitCommit> = output
+pub(crate) fn parse_commits(output: &str) -> Vec<GitCommit> {
+    output
         .split("---\n")
         .filter(|s| !s.trim().is_empty())
         .filter_map(|commit_str| {
@@ -139,9 +254,14 @@ pub fn git_log(limit: usize) -> Result<Vec<GitCommit>, String> {
                 None
             }
         })
-        .collect();
+        .collect()
+}
 
-    Ok(commits)
+#[tauri::command]
+pub fn git_log(limit: usize) -> Result<Vec<GitCommit>, String> {
+    let output = run_git_command(&["log", &format!("-{}", limit), &format!("--format={}", LOG_FORMAT)])?;
+
+    Ok(parse_commits(&output))
 }
 
 #[tauri::command]
@@ -165,5 +285,7 @@ pub fn git_init() -> Result<String, String> {
         return Err(format!("Git init failed: {}", stderr));
     }
 
+    ensure_gitignore_rules()?;
+
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
@@ -0,0 +1,94 @@
+use std::fs;
+
+use super::git::{get_zsh_config_dir, run_git_command};
+
+/// Files that must never be committed: machine-specific config, secrets and shell state
+pub(crate) const LOCAL_FILE_PATTERNS: &[&str] = &[
+    "*.local.zsh",
+    ".zshrc.local",
+    "functions.local",
+    ".zshrc.secrets",
+    ".zsh_history",
+    ".zcompdump*",
+];
+
+const GITIGNORE_HEADER: &str = "# Local and secret files (managed by ZSH Config Manager)";
+
+/// Match a file name against a pattern where `*` stands for any sequence of characters
+fn glob_match(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(remaining) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=remaining.len())
+                .filter(|&i| remaining.is_char_boundary(i))
+                .any(|i| glob_match(rest, &remaining[i..]))
+        }
+    }
+}
+
+/// Whether a repository path is a local or secret file, or lies in a local directory such as functions.local/
+pub(crate) fn is_local_file(path: &str) -> bool {
+    path.split('/')
+        .any(|name| LOCAL_FILE_PATTERNS.iter().any(|pattern| glob_match(pattern, name)))
+}
+
+/// Add any missing local/secret rules to ~/.zsh/.gitignore and return the rules that were added
+pub(crate) fn ensure_gitignore_rules() -> Result<Vec<String>, String> {
+    let path = get_zsh_config_dir().join(".gitignore");
+
+    let mut content = if path.exists() {
+        fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read .gitignore: {}", e))?
+    } else {
+        String::new()
+    };
+
+    let missing: Vec<String> = LOCAL_FILE_PATTERNS
+        .iter()
+        .filter(|pattern| !content.lines().any(|line| line.trim() == **pattern))
+        .map(|pattern| pattern.to_string())
+        .collect();
+
+    if missing.is_empty() {
+        return Ok(missing);
+    }
+
+    if !content.ends_with('\n') && !content.is_empty() {
+        content.push('\n');
+    }
+    if !content.contains(GITIGNORE_HEADER) {
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        content.push_str(GITIGNORE_HEADER);
+        content.push('\n');
+    }
+    for rule in &missing {
+        content.push_str(rule);
+        content.push('\n');
+    }
+
+    fs::write(&path, content)
+        .map_err(|e| format!("Failed to write .gitignore: {}", e))?;
+
+    Ok(missing)
+}
+
+/// Tracked files that match a local/secret pattern
+pub(crate) fn find_policy_violations() -> Result<Vec<String>, String> {
+    let output = run_git_command(&["ls-files", "-z"])?;
+
+    Ok(output
+        .split('\0')
+        .filter(|path| !path.is_empty() && is_local_file(path))
+        .map(|path| path.to_string())
+        .collect())
+}
+
+#[tauri::command]
+pub fn ensure_gitignore() -> Result<Vec<String>, String> {
+    ensure_gitignore_rules()
+}
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+
+use super::alias::{add_alias, delete_alias, list_aliases, update_alias};
+use super::diff::{parse_entries, EntryKind};
+use super::function::{add_function, delete_function, list_functions, update_function};
+use super::git::{parse_commits, run_git_command, GitCommit, LOG_FORMAT};
+
+/// The definition of an alias or function as introduced by a commit.
+/// `value` is None when the commit removed it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EntryVersion {
+    pub commit: GitCommit,
+    pub value: Option<String>,
+}
+
+/// Versioned file holding the entry (history only exists for shared entries)
+fn entry_file(kind: &EntryKind) -> Result<&'static str, String> {
+    match kind {
+        EntryKind::Alias => Ok("aliases.zsh"),
+        EntryKind::Function => Ok("functions.zsh"),
+        EntryKind::Plugin => Err("History is only available for aliases and functions".to_string()),
+    }
+}
+
+fn value_at(kind: &EntryKind, file: &str, name: &str, commit: &str) -> Option<String> {
+    let content = run_git_command(&["show", &format!("{}:{}", commit, file)]).ok()?;
+    let mut entries = parse_entries(&content);
+
+    match kind {
+        EntryKind::Alias => entries.aliases.remove(name),
+        EntryKind::Function => entries.functions.remove(name),
+        EntryKind::Plugin => None,
+    }
+}
+
+/// Remove the indentation the function parser leaves on body lines after the first,
+/// so `update_function` does not indent the restored body twice
+fn dedent_body(content: &str) -> String {
+    let is_indent = |c: char| c == ' ' || c == '\t';
+    let indent = content
+        .lines()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.chars().take_while(|&c| is_indent(c)).count())
+        .min()
+        .unwrap_or(0);
+
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 {
+                return line.trim_start();
+            }
+            // At most `indent` ASCII spaces or tabs, one byte each, so other whitespace is never cut
+            let end = line.chars().take(indent).take_while(|&c| is_indent(c)).count();
+            &line[end..]
+        })
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// Every commit that changed the definition of an alias or function, newest first
+#[tauri::command]
+pub fn entry_history(kind: EntryKind, name: String) -> Result<Vec<EntryVersion>, String> {
+    let file = entry_file(&kind)?;
+    let output = run_git_command(&["log", &format!("--format={}", LOG_FORMAT), "--", file])?;
+
+    let mut versions = Vec::new();
+    let mut previous: Option<String> = None;
+
+    // Walk from the oldest commit so each version is attributed to the commit that introduced it
+    for commit in parse_commits(&output).into_iter().rev() {
+        let value = value_at(&kind, file, &name, &commit.hash);
+        if value != previous {
+            previous = value.clone();
+            versions.push(EntryVersion { commit, value });
+        }
+    }
+
+    versions.reverse();
+    Ok(versions)
+}
+
+/// Put back the definition an entry had at `commit` (removing it if it did not exist then)
+#[tauri::command]
+pub fn restore_entry_version(kind: EntryKind, name: String, commit: String) -> Result<(), String> {
+    let file = entry_file(&kind)?;
+    run_git_command(&["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", commit)])
+        .map_err(|_| format!("Unknown commit '{}'", commit))?;
+
+    let value = value_at(&kind, file, &name, &commit);
+
+    match kind {
+        EntryKind::Alias => {
+            let exists = list_aliases(true)?.iter().any(|a| a.name == name);
+            match (value, exists) {
+                (Some(command), true) => update_alias(name.clone(), name, command, true),
+                (Some(command), false) => add_alias(name, command, true),
+                (None, true) => delete_alias(name, true),
+                (None, false) => Ok(()),
+            }
+        }
+        EntryKind::Function => {
+            let exists = list_functions(true)?.iter().any(|f| f.name == name);
+            match (value.map(|content| dedent_body(&content)), exists) {
+                (Some(content), true) => update_function(name, content, true),
+                (Some(content), false) => add_function(name, content, true),
+                (None, true) => delete_function(name, true),
+                (None, false) => Ok(()),
+            }
+        }
+        EntryKind::Plugin => Ok(()),
+    }
+}
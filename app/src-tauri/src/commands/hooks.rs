@@ -0,0 +1,317 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::git::{get_zsh_config_dir, run_git_command};
+use super::gitignore::is_local_file;
+use super::secrets::scan_content;
+
+/// Hooks installed in the config repository
+const HOOK_NAMES: &[&str] = &["pre-commit", "pre-push"];
+
+/// Marks hook scripts written by the app, so user hooks are never overwritten or deleted
+const HOOK_MARKER: &str = "# Installed by ZSH Config Manager";
+
+/// Suffix of the user's previous hook, kept while ours is installed and chained from it
+const BACKUP_SUFFIX: &str = ".pre-zsh-config";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitHook {
+    pub name: String,
+    pub installed: bool,
+    /// A hook that was there before ours and runs before the app's checks
+    pub previous: bool,
+}
+
+fn get_hooks_dir() -> Result<PathBuf, String> {
+    // Honours core.hooksPath
+    let output = run_git_command(&["rev-parse", "--git-path", "hooks"])?;
+    Ok(get_zsh_config_dir().join(output.trim()))
+}
+
+fn backup_path(hook: &Path) -> PathBuf {
+    let mut name = hook.as_os_str().to_owned();
+    name.push(BACKUP_SUFFIX);
+    PathBuf::from(name)
+}
+
+fn is_our_hook(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .map(|content| content.contains(HOOK_MARKER))
+        .unwrap_or(false)
+}
+
+/// Shell script run by git: the previous hook first, then the app's headless checks.
+/// Commits are not blocked when the app has been removed.
+fn hook_script(name: &str, exe: &str) -> String {
+    // pre-push gets the pushed refs on stdin, which the previous hook and ours both read
+    let (capture, feed) = if name == "pre-push" {
+        ("input=$(cat)\n", "printf '%s\\n' \"$input\" | ")
+    } else {
+        ("", "")
+    };
+
+    format!(
+        "#!/bin/sh\n\
+         {marker}\n\
+         {capture}\
+         previous=\"$0{suffix}\"\n\
+         if [ -x \"$previous\" ]; then\n\
+         \x20   {feed}\"$previous\" \"$@\" || exit $?\n\
+         fi\n\
+         app='{exe}'\n\
+         if [ ! -x \"$app\" ]; then\n\
+         \x20   echo \"zsh-config: $app not found, skipping checks\" >&2\n\
+         \x20   exit 0\n\
+         fi\n\
+         {feed}exec \"$app\" hook {name} \"$@\"\n",
+        marker = HOOK_MARKER,
+        suffix = BACKUP_SUFFIX,
+        capture = capture,
+        feed = feed,
+        exe = exe.replace('\'', "'\\''"),
+        name = name,
+    )
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+/// Syntax-check zsh code without running it. None when it parses or zsh is not available.
+pub(crate) fn check_zsh_syntax(content: &str) -> Option<String> {
+    let mut child = Command::new("zsh")
+        .arg("-n")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(content.as_bytes());
+    }
+
+    let output = child.wait_with_output().ok()?;
+    if output.status.success() {
+        None
+    } else {
+        Some(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+fn is_zsh_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.ends_with(".zsh") || name == ".zshrc" || name == "zshrc"
+}
+
+/// Run the local-file policy, zsh syntax check and secret scan on files read through `read`
+fn check_files(paths: &[String], read: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for path in paths {
+        if is_local_file(path) {
+            problems.push(format!("{}: local file must not be committed", path));
+            continue;
+        }
+
+        let Some(content) = read(path) else {
+            continue;
+        };
+
+        if is_zsh_file(path) {
+            if let Some(error) = check_zsh_syntax(&content) {
+                problems.push(format!("{}: zsh syntax error: {}", path, error));
+            }
+        }
+
+        for finding in scan_content(path, &content) {
+            problems.push(format!(
+                "{}:{}: possible {} ({})",
+                finding.file, finding.line, finding.rule, finding.excerpt
+            ));
+        }
+    }
+
+    problems
+}
+
+fn split_paths(output: &str) -> Vec<String> {
+    output
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(|path| path.to_string())
+        .collect()
+}
+
+/// Check what is about to be committed: the staged content, not the working tree
+fn check_staged() -> Result<Vec<String>, String> {
+    let output = run_git_command(&["diff", "--cached", "--name-only", "-z", "--diff-filter=ACMR"])?;
+    let paths = split_paths(&output);
+
+    Ok(check_files(&paths, |path| run_git_command(&["show", &format!(":{}", path)]).ok()))
+}
+
+fn is_zero_sha(sha: &str) -> bool {
+    sha.chars().all(|c| c == '0')
+}
+
+/// Commits a pushed ref sends: those after the remote sha, or those on no remote yet for a new ref
+fn pushed_commits(local: &str, remote: &str) -> Result<Vec<String>, String> {
+    let output = if is_zero_sha(remote) {
+        run_git_command(&["rev-list", local, "--not", "--remotes"])?
+    } else {
+        // The remote sha is unknown here after a force push elsewhere
+        run_git_command(&["rev-list", &format!("{}..{}", remote, local)])
+            .or_else(|_| run_git_command(&["rev-list", local, "--not", "--remotes"]))?
+    };
+
+    Ok(output.lines().map(|sha| sha.to_string()).collect())
+}
+
+/// Check the files changed by every pushed commit, read from the
+/// "<local ref> <local sha> <remote ref> <remote sha>" lines on stdin
+fn check_pushed() -> Result<Vec<String>, String> {
+    let refs: Vec<(String, String)> = std::io::stdin()
+        .lock()
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [_, local, _, remote] => Some((local.to_string(), remote.to_string())),
+                _ => None,
+            }
+        })
+        // A local sha of zeros means the remote ref is being deleted
+        .filter(|(local, _)| !is_zero_sha(local))
+        .collect();
+
+    let mut commits: Vec<String> = Vec::new();
+    for (local, remote) in &refs {
+        for commit in pushed_commits(local, remote)? {
+            if !commits.contains(&commit) {
+                commits.push(commit);
+            }
+        }
+    }
+
+    let mut problems = Vec::new();
+    for commit in &commits {
+        let output = run_git_command(&[
+            "diff-tree", "-r", "--root", "--no-commit-id", "--name-only", "-z", "--diff-filter=ACMR", commit,
+        ])?;
+        let paths = split_paths(&output);
+        for problem in check_files(&paths, |path| run_git_command(&["show", &format!("{}:{}", commit, path)]).ok()) {
+            if !problems.contains(&problem) {
+                problems.push(problem);
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Entry point for the installed hooks (`hook <name>`). Returns the exit code git expects.
+pub fn run_hook(name: &str) -> i32 {
+    let result = match name {
+        "pre-commit" => check_staged(),
+        "pre-push" => check_pushed(),
+        _ => {
+            eprintln!("Unknown hook '{}'", name);
+            return 2;
+        }
+    };
+
+    match result {
+        Ok(problems) if problems.is_empty() => 0,
+        Ok(problems) => {
+            eprintln!("zsh-config: {} blocked:", name);
+            for problem in &problems {
+                eprintln!("  {}", problem);
+            }
+            eprintln!("Fix these or use --no-verify to skip the checks.");
+            1
+        }
+        Err(e) => {
+            eprintln!("zsh-config: {} check failed: {}", name, e);
+            2
+        }
+    }
+}
+
+#[tauri::command]
+pub fn git_hooks_status() -> Result<Vec<GitHook>, String> {
+    let hooks_dir = get_hooks_dir()?;
+
+    Ok(HOOK_NAMES
+        .iter()
+        .map(|name| {
+            let path = hooks_dir.join(name);
+            GitHook {
+                name: name.to_string(),
+                installed: is_our_hook(&path),
+                previous: backup_path(&path).exists(),
+            }
+        })
+        .collect())
+}
+
+/// Install or update the hooks, keeping any existing hook so it still runs and can be restored
+#[tauri::command]
+pub fn install_git_hooks() -> Result<Vec<GitHook>, String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to locate application binary: {}", e))?;
+    let hooks_dir = get_hooks_dir()?;
+    fs::create_dir_all(&hooks_dir)
+        .map_err(|e| format!("Failed to create hooks directory: {}", e))?;
+
+    for name in HOOK_NAMES {
+        let path = hooks_dir.join(name);
+
+        if path.exists() && !is_our_hook(&path) {
+            fs::rename(&path, backup_path(&path))
+                .map_err(|e| format!("Failed to back up existing {} hook: {}", name, e))?;
+        }
+
+        fs::write(&path, hook_script(name, &exe.to_string_lossy()))
+            .map_err(|e| format!("Failed to write {} hook: {}", name, e))?;
+        make_executable(&path)?;
+    }
+
+    git_hooks_status()
+}
+
+/// Remove the app's hooks and put back the ones they replaced
+#[tauri::command]
+pub fn uninstall_git_hooks() -> Result<Vec<GitHook>, String> {
+    let hooks_dir = get_hooks_dir()?;
+
+    for name in HOOK_NAMES {
+        let path = hooks_dir.join(name);
+        if !is_our_hook(&path) {
+            continue;
+        }
+
+        fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove {} hook: {}", name, e))?;
+
+        let backup = backup_path(&path);
+        if backup.exists() {
+            fs::rename(&backup, &path)
+                .map_err(|e| format!("Failed to restore previous {} hook: {}", name, e))?;
+        }
+    }
+
+    git_hooks_status()
+}
@@ -0,0 +1,469 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use super::alias::parse_alias_line;
+use super::conflict::{list_conflicted_paths, read_stage};
+use super::diff::{managed_files, MANAGED_DIRS, PARSED_FILES};
+use super::function::try_parse_function_start;
+use super::git::{get_zsh_config_dir, run_git_command};
+use super::plugin::{find_plugins_array, parse_plugins_from_file};
+use super::plugin_lock::LOCKFILE_NAME;
+use super::plugin_managers::oh_my_zsh::{insert_entry, remove_entry};
+
+/// Name of the merge driver in git config and .gitattributes
+const MERGE_DRIVER_NAME: &str = "zsh-config";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChunkKind {
+    Alias,
+    Function,
+    Plugins,
+    Line,
+    Trailer,
+}
+
+/// An entry changed differently on both sides
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MergeConflict {
+    pub kind: ChunkKind,
+    pub name: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MergeResult {
+    pub content: String,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ChunkKey {
+    kind: ChunkKind,
+    name: String,
+    occurrence: usize,
+}
+
+/// A definition together with the comments and blank lines right above it
+#[derive(Debug, Clone)]
+struct Chunk {
+    key: ChunkKey,
+    text: String,
+}
+
+fn is_trivia(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+/// Index of the last line of the function starting at `start`, using the same brace counting as `parse_functions`
+fn function_end(lines: &[&str], start: usize) -> usize {
+    let mut brace_count = 0;
+    let mut started = false;
+
+    for (j, line) in lines.iter().enumerate().skip(start) {
+        for ch in line.chars() {
+            if ch == '{' {
+                brace_count += 1;
+                started = true;
+            } else if ch == '}' {
+                brace_count -= 1;
+            }
+        }
+
+        if started && brace_count == 0 {
+            return j;
+        }
+    }
+
+    lines.len() - 1
+}
+
+/// Index of the line closing the plugins array starting at `start`, skipping `)` in comments
+fn plugins_end(lines: &[&str], start: usize) -> usize {
+    let rest = lines[start..].join("\n");
+    find_plugins_array(&rest)
+        .map(|array| start + rest[..array.close].matches('\n').count())
+        .unwrap_or(lines.len() - 1)
+}
+
+/// Name of a scalar `NAME=value` assignment, optionally behind export/typeset and their flags
+fn assignment_name(line: &str) -> Option<String> {
+    let mut rest = line.trim();
+    loop {
+        let (word, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if ["export", "typeset", "declare", "local", "readonly", "integer", "float"].contains(&word)
+            || (word.starts_with('-') && rest != line.trim())
+        {
+            rest = tail.trim_start();
+        } else {
+            break;
+        }
+    }
+
+    let (name, value) = rest.split_once('=')?;
+    let valid = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    // Arrays such as fpath=(... $fpath) are often set several times, so they keep their text as key
+    (valid && !value.starts_with('(')).then(|| name.to_string())
+}
+
+/// Key of a plain line: assignments by variable, options by option name and zstyles by context and style,
+/// so both sides changing the same setting meet on one key instead of both being kept
+fn line_name(line: &str) -> String {
+    let words: Vec<&str> = line.split_whitespace().take_while(|word| !word.starts_with('#')).collect();
+    match words.as_slice() {
+        ["setopt" | "unsetopt", option] => format!("option {}", option.to_lowercase().replace('_', "")),
+        ["zstyle", context, style, ..] if !context.starts_with('-') => format!("zstyle {} {}", context, style),
+        _ => assignment_name(line).unwrap_or_else(|| line.trim().to_string()),
+    }
+}
+
+fn split_chunks(content: &str) -> Vec<Chunk> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut chunks = Vec::new();
+    let mut occurrences: HashMap<(ChunkKind, String), usize> = HashMap::new();
+    let mut pending = String::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+
+        if is_trivia(line) {
+            pending.push_str(line);
+            pending.push('\n');
+            i += 1;
+            continue;
+        }
+
+        let (kind, name, end) = if let Some(alias) = parse_alias_line(line) {
+            (ChunkKind::Alias, alias.name, i)
+        } else if ["plugins=(", "plugins+=("].iter().any(|a| line.trim_start().starts_with(a)) {
+            (ChunkKind::Plugins, "plugins".to_string(), plugins_end(&lines, i))
+        } else if let Some(name) = try_parse_function_start(line) {
+            (ChunkKind::Function, name, function_end(&lines, i))
+        } else {
+            (ChunkKind::Line, line_name(line), i)
+        };
+
+        let counter = occurrences.entry((kind.clone(), name.clone())).or_insert(0);
+        let key = ChunkKey { kind, name, occurrence: *counter };
+        *counter += 1;
+
+        let mut text = std::mem::take(&mut pending);
+        for block_line in &lines[i..=end] {
+            text.push_str(block_line);
+            text.push('\n');
+        }
+
+        chunks.push(Chunk { key, text });
+        i = end + 1;
+    }
+
+    if !pending.is_empty() {
+        chunks.push(Chunk {
+            key: ChunkKey { kind: ChunkKind::Trailer, name: String::new(), occurrence: 0 },
+            text: pending,
+        });
+    }
+
+    chunks
+}
+
+/// Keep our order and insert entries only they have after their nearest preceding neighbour,
+/// past any entries we added at the same spot, so appends from both sides end up one after the other.
+fn merge_order(ours: &[ChunkKey], theirs: &[ChunkKey], base: &HashSet<&ChunkKey>) -> Vec<ChunkKey> {
+    let theirs_set: HashSet<&ChunkKey> = theirs.iter().collect();
+    let mut order: Vec<ChunkKey> = ours.to_vec();
+
+    for (i, key) in theirs.iter().enumerate() {
+        if order.contains(key) {
+            continue;
+        }
+
+        let mut pos = theirs[..i]
+            .iter()
+            .rev()
+            .find_map(|prev| order.iter().position(|k| k == prev).map(|p| p + 1))
+            .unwrap_or(0);
+
+        while pos < order.len()
+            && order[pos].kind != ChunkKind::Trailer
+            && !base.contains(&order[pos])
+            && !theirs_set.contains(&order[pos])
+        {
+            pos += 1;
+        }
+
+        order.insert(pos, key.clone());
+    }
+
+    // Trailing comments stay at the end of the file
+    if let Some(pos) = order.iter().position(|k| k.kind == ChunkKind::Trailer) {
+        let trailer = order.remove(pos);
+        order.push(trailer);
+    }
+
+    order
+}
+
+fn merge_plugin_lists(base: &[String], ours: &[String], theirs: &[String]) -> Vec<String> {
+    // Drop plugins they removed
+    let mut merged: Vec<String> = ours
+        .iter()
+        .filter(|p| !base.contains(p) || theirs.contains(p))
+        .cloned()
+        .collect();
+
+    // Add plugins they added, after the same neighbour as on their side
+    for (i, plugin) in theirs.iter().enumerate() {
+        if merged.contains(plugin) || base.contains(plugin) {
+            continue;
+        }
+
+        let pos = theirs[..i]
+            .iter()
+            .rev()
+            .find_map(|prev| merged.iter().position(|p| p == prev).map(|p| p + 1))
+            .unwrap_or(merged.len());
+        merged.insert(pos, plugin.clone());
+    }
+
+    merged
+}
+
+/// Turn the plugins array in `text` into `plugins` by removing and inserting entries in place,
+/// so the layout and inline comments of the kept entries survive
+fn render_plugins(text: &str, plugins: &[String]) -> String {
+    let mut content = text.to_string();
+
+    // One at a time since offsets shift
+    while let Some(array) = find_plugins_array(&content) {
+        let Some(entry) = array.entries.iter().find(|entry| !plugins.contains(&entry.name)) else {
+            break;
+        };
+        content = remove_entry(&content, &array, entry);
+    }
+
+    for (i, plugin) in plugins.iter().enumerate() {
+        let Some(array) = find_plugins_array(&content) else {
+            break;
+        };
+        if array.entries.iter().any(|entry| entry.name == *plugin) {
+            continue;
+        }
+
+        // After the nearest preceding plugin that is already there
+        let index = plugins[..i]
+            .iter()
+            .rev()
+            .find_map(|prev| array.entries.iter().rposition(|entry| entry.name == *prev).map(|p| p + 1))
+            .unwrap_or(0);
+        content = insert_entry(&content, &array, index, plugin);
+    }
+
+    content
+}
+
+/// Three-way merge of a managed file, entry by entry.
+/// Only entries changed differently on both sides are reported as conflicts.
+pub(crate) fn merge_contents(base: &str, ours: &str, theirs: &str) -> MergeResult {
+    let base_chunks = split_chunks(base);
+    let our_chunks = split_chunks(ours);
+    let their_chunks = split_chunks(theirs);
+
+    let index = |chunks: &[Chunk]| -> HashMap<ChunkKey, String> {
+        chunks.iter().map(|c| (c.key.clone(), c.text.clone())).collect()
+    };
+    let base_map = index(&base_chunks);
+    let our_map = index(&our_chunks);
+    let their_map = index(&their_chunks);
+
+    let base_keys: HashSet<&ChunkKey> = base_map.keys().collect();
+    let our_keys: Vec<ChunkKey> = our_chunks.iter().map(|c| c.key.clone()).collect();
+    let their_keys: Vec<ChunkKey> = their_chunks.iter().map(|c| c.key.clone()).collect();
+
+    let mut content = String::new();
+    let mut conflicts = Vec::new();
+
+    for key in merge_order(&our_keys, &their_keys, &base_keys) {
+        let b = base_map.get(&key);
+        let o = our_map.get(&key);
+        let t = their_map.get(&key);
+
+        let resolved = if o == t || t == b {
+            o.cloned()
+        } else if o == b {
+            t.cloned()
+        } else if let (ChunkKind::Plugins, Some(b), Some(o), Some(t)) = (&key.kind, b, o, t) {
+            let merged = merge_plugin_lists(
+                &parse_plugins_from_file(b),
+                &parse_plugins_from_file(o),
+                &parse_plugins_from_file(t),
+            );
+            Some(render_plugins(o, &merged))
+        } else {
+            content.push_str("<<<<<<< ours\n");
+            content.push_str(o.map(String::as_str).unwrap_or(""));
+            content.push_str("=======\n");
+            content.push_str(t.map(String::as_str).unwrap_or(""));
+            content.push_str(">>>>>>> theirs\n");
+
+            conflicts.push(MergeConflict {
+                kind: key.kind.clone(),
+                name: key.name.clone(),
+                base: b.cloned(),
+                ours: o.cloned(),
+                theirs: t.cloned(),
+            });
+            None
+        };
+
+        if let Some(text) = resolved {
+            content.push_str(&text);
+        }
+    }
+
+    MergeResult { content, conflicts }
+}
+
+/// Entry point for `git merge` when registered as a merge driver (`%O %A %B`).
+/// Writes the result over the "ours" file and returns the exit code git expects.
+pub fn run_merge_driver(base_path: &str, ours_path: &str, theirs_path: &str) -> i32 {
+    let read = |path: &str| fs::read_to_string(path).unwrap_or_default();
+    let result = merge_contents(&read(base_path), &read(ours_path), &read(theirs_path));
+
+    if let Err(e) = fs::write(ours_path, &result.content) {
+        eprintln!("Failed to write merge result: {}", e);
+        return 2;
+    }
+
+    for conflict in &result.conflicts {
+        eprintln!("Conflict on {:?} '{}'", conflict.kind, conflict.name);
+    }
+
+    if result.conflicts.is_empty() {
+        0
+    } else {
+        1
+    }
+}
+
+#[tauri::command]
+pub fn merge_config_file(base: String, ours: String, theirs: String) -> Result<MergeResult, String> {
+    Ok(merge_contents(&base, &ours, &theirs))
+}
+
+/// Structurally merge conflicted managed files and mark the clean ones as resolved.
+/// Returns the files that still need manual resolution.
+#[tauri::command]
+pub fn git_auto_merge_conflicts() -> Result<Vec<String>, String> {
+    let mut remaining = Vec::new();
+    let managed = managed_files();
+
+    for path in list_conflicted_paths()? {
+        // The lockfile is JSON, not zsh
+        if !managed.contains(&path) || path == LOCKFILE_NAME {
+            remaining.push(path);
+            continue;
+        }
+
+        let stage = |n: u8| read_stage(&path, n).unwrap_or_default();
+        let result = merge_contents(&stage(1), &stage(2), &stage(3));
+
+        if result.conflicts.is_empty() {
+            fs::write(get_zsh_config_dir().join(&path), &result.content)
+                .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+            run_git_command(&["add", "--", &path])?;
+        } else {
+            remaining.push(path);
+        }
+    }
+
+    Ok(remaining)
+}
+
+/// Register the structural merge as a git merge driver for the managed files
+#[tauri::command]
+pub fn install_merge_driver() -> Result<String, String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to locate application binary: {}", e))?;
+
+    let driver = format!("'{}' merge-driver %O %A %B", exe.display().to_string().replace('\'', "'\\''"));
+    run_git_command(&["config", &format!("merge.{}.name", MERGE_DRIVER_NAME), "ZSH config structural merge"])?;
+    run_git_command(&["config", &format!("merge.{}.driver", MERGE_DRIVER_NAME), &driver])?;
+
+    let attributes_path = get_zsh_config_dir().join(".gitattributes");
+    let mut content = if attributes_path.exists() {
+        fs::read_to_string(&attributes_path)
+            .map_err(|e| format!("Failed to read .gitattributes: {}", e))?
+    } else {
+        String::new()
+    };
+
+    let patterns = PARSED_FILES
+        .iter()
+        .map(|file| file.to_string())
+        .chain(MANAGED_DIRS.iter().map(|dir| format!("{}/*", dir)));
+    for pattern in patterns {
+        let rule = format!("{} merge={}", pattern, MERGE_DRIVER_NAME);
+        if !content.lines().any(|line| line.trim() == rule) {
+            if !content.ends_with('\n') && !content.is_empty() {
+                content.push('\n');
+            }
+            content.push_str(&rule);
+            content.push('\n');
+        }
+    }
+
+    fs::write(&attributes_path, content)
+        .map_err(|e| format!("Failed to write .gitattributes: {}", e))?;
+
+    Ok(format!("Merge driver '{}' installed", MERGE_DRIVER_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(content: &str) -> Vec<String> {
+        split_chunks(content).into_iter().map(|chunk| chunk.key.name).collect()
+    }
+
+    #[test]
+    fn keys_settings_by_name() {
+        let content = "export EDITOR=vim\nHISTSIZE=1000\nsetopt AUTO_CD\nzstyle ':completion:*' menu select\nfpath=(~/f $fpath)\n";
+        assert_eq!(
+            names(content),
+            vec!["EDITOR", "HISTSIZE", "option autocd", "zstyle ':completion:*' menu", "fpath=(~/f $fpath)"]
+        );
+    }
+
+    #[test]
+    fn same_setting_changed_on_both_sides_conflicts() {
+        let base = "export EDITOR=vim\nsetopt autocd\n";
+        let ours = "export EDITOR=nvim\nunsetopt autocd\n";
+        let theirs = "export EDITOR=emacs\nsetopt AUTO_CD\n";
+
+        let result = merge_contents(base, ours, theirs);
+        let conflicts: Vec<&str> = result.conflicts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(conflicts, vec!["EDITOR", "option autocd"]);
+        assert_eq!(result.content.matches("EDITOR=").count(), 2);
+        assert!(result.content.contains("<<<<<<< ours\nexport EDITOR=nvim\n=======\nexport EDITOR=emacs\n>>>>>>> theirs\n"));
+    }
+
+    #[test]
+    fn setting_changed_on_one_side_merges() {
+        let base = "export EDITOR=vim\nzstyle ':completion:*' menu select\n";
+        let ours = "export EDITOR=nvim\nzstyle ':completion:*' menu select\n";
+        let theirs = "export EDITOR=vim\nzstyle ':completion:*' menu no\nexport PAGER=less\n";
+
+        let result = merge_contents(base, ours, theirs);
+        assert!(result.conflicts.is_empty());
+        assert_eq!(result.content, "export EDITOR=nvim\nzstyle ':completion:*' menu no\nexport PAGER=less\n");
+    }
+}
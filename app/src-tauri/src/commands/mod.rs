@@ -1,5 +1,26 @@
 pub mod alias;
 pub mod function;
+pub mod autoload;
 pub mod config;
 pub mod git;
 pub mod plugin;
+pub mod plugin_managers;
+pub mod plugin_install;
+pub mod plugin_lock;
+pub mod plugin_catalog;
+pub mod plugin_definitions;
+pub mod theme;
+pub mod completion;
+pub mod navigation;
+pub mod diff;
+pub mod conflict;
+pub mod merge;
+pub mod branch;
+pub mod setup;
+pub mod gitignore;
+pub mod history;
+pub mod snapshot;
+pub mod progress;
+pub mod secrets;
+pub mod sync;
+pub mod hooks;
@@ -0,0 +1,496 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use regex::Regex;
+
+use super::git::get_zsh_config_dir;
+use super::gitignore::is_local_file;
+use super::plugin_managers::{config_files, get_home_dir, get_zshrc_local_path, read_config, relative_to_home, write_config};
+
+/// A `hash -d name=path` entry, used as `cd ~name`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NamedDir {
+    pub name: String,
+    /// The path as written, e.g. "$HOME/work/projects"
+    pub path: String,
+    /// The path on this machine
+    pub expanded: String,
+    pub exists: bool,
+    /// False for a shared entry written as an absolute path under this machine's home directory
+    pub portable: bool,
+    pub shared: bool,
+    /// File relative to the home directory, and 1-based line
+    pub file: String,
+    pub line: usize,
+}
+
+/// A directory of a `cdpath=(...)` or `cdpath+=(...)` array
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CdpathEntry {
+    pub path: String,
+    pub expanded: String,
+    pub exists: bool,
+    pub portable: bool,
+    pub shared: bool,
+    pub file: String,
+    pub line: usize,
+}
+
+/// A word of a config line with its position, quotes included
+struct Word {
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+/// Where new entries go: config.zsh when shared, .zshrc.local otherwise
+fn target_file(shared: bool) -> PathBuf {
+    if shared {
+        get_zsh_config_dir().join("config.zsh")
+    } else {
+        get_zshrc_local_path()
+    }
+}
+
+/// Words split on whitespace outside quotes, stopping at a comment
+fn split_words(line: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut quote: Option<char> = None;
+
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '#') if start.is_none() => break,
+            (None, c) if c.is_whitespace() => {
+                if let Some(begin) = start.take() {
+                    words.push(Word { text: line[begin..index].to_string(), start: begin, end: index });
+                }
+            }
+            (None, c) => {
+                if c == '"' || c == '\'' {
+                    quote = Some(c);
+                }
+                start.get_or_insert(index);
+            }
+        }
+    }
+    if let Some(begin) = start {
+        words.push(Word { text: line[begin..].to_string(), start: begin, end: line.len() });
+    }
+
+    words
+}
+
+fn unquote(word: &str) -> String {
+    word.replace(['"', '\''], "")
+}
+
+fn expand_path(path: &str) -> String {
+    let home = get_home_dir().to_string_lossy().to_string();
+    let path = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("{}{}", home, rest),
+        _ => path.to_string(),
+    };
+    path.replace("${HOME}", &home).replace("$HOME", &home)
+}
+
+/// Write paths under the home directory as `$HOME/...` so they resolve on every machine
+pub(crate) fn portable_path(path: &str) -> String {
+    let home = get_home_dir().to_string_lossy().to_string();
+    let expanded = expand_path(path.trim());
+    match expanded.strip_prefix(&home) {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("$HOME{}", rest),
+        _ => expanded,
+    }
+}
+
+/// The portable path inside double quotes; paths that could end the quotes or run a command are rejected
+fn quoted_path(path: &str) -> Result<String, String> {
+    let path = portable_path(path);
+    if path.is_empty() || path.contains(['"', '`', '\\', '\n']) || path.contains("$(") {
+        return Err(format!("Invalid directory path '{}'", path));
+    }
+    Ok(format!("\"{}\"", path))
+}
+
+fn is_portable(path: &str, shared: bool) -> bool {
+    !shared || portable_path(path) == path || !path.starts_with('/')
+}
+
+fn exists(expanded: &str) -> bool {
+    !expanded.contains('$') && Path::new(expanded).is_dir()
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c)) {
+        Ok(())
+    } else {
+        Err(format!("Invalid directory name '{}'", name))
+    }
+}
+
+/// `name=path` words of a `hash -d` line
+fn parse_hash_line(line: &str) -> Vec<(Word, String, String)> {
+    let words = split_words(line);
+    if words.len() < 3 || words[0].text != "hash" || words[1].text != "-d" {
+        return Vec::new();
+    }
+
+    words
+        .into_iter()
+        .skip(2)
+        .filter_map(|word| {
+            let (name, path) = word.text.split_once('=')?;
+            let (name, path) = (name.to_string(), unquote(path));
+            Some((word, name, path))
+        })
+        .collect()
+}
+
+/// Named directories of one file
+fn named_dirs_in(file: &Path) -> Result<Vec<NamedDir>, String> {
+    let Some(content) = read_config(file)? else {
+        return Ok(Vec::new());
+    };
+    let relative = relative_to_home(file);
+    let shared = !is_local_file(&relative);
+
+    Ok(content
+        .lines()
+        .enumerate()
+        .flat_map(|(index, line)| {
+            parse_hash_line(line)
+                .into_iter()
+                .map(|(_, name, path)| {
+                    let expanded = expand_path(&path);
+                    NamedDir {
+                        name,
+                        exists: exists(&expanded),
+                        portable: is_portable(&path, shared),
+                        expanded,
+                        path,
+                        shared,
+                        file: relative.clone(),
+                        line: index + 1,
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect())
+}
+
+/// Array words of the `cdpath` lines of a file with their 0-based line, `$cdpath` itself excluded
+fn cdpath_words(content: &str) -> Vec<(usize, Word)> {
+    let start = Regex::new(r"^\s*(?:typeset\s+-U\s+)?cdpath\+?=\(").unwrap();
+    let mut words = Vec::new();
+    let mut in_array = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let offset = match start.find(line) {
+            Some(m) => m.end(),
+            None if in_array => 0,
+            None => continue,
+        };
+        in_array = true;
+
+        for mut word in split_words(&line[offset..]) {
+            let closes = word.text.ends_with(')');
+            word.text = word.text.trim_end_matches(')').to_string();
+            word.start += offset;
+            word.end = word.start + word.text.len();
+            if !word.text.is_empty() && !word.text.contains("cdpath") {
+                words.push((index, word));
+            }
+            if closes {
+                in_array = false;
+                break;
+            }
+        }
+    }
+
+    words
+}
+
+fn cdpath_in(file: &Path) -> Result<Vec<CdpathEntry>, String> {
+    let Some(content) = read_config(file)? else {
+        return Ok(Vec::new());
+    };
+    let relative = relative_to_home(file);
+    let shared = !is_local_file(&relative);
+
+    Ok(cdpath_words(&content)
+        .into_iter()
+        .map(|(index, word)| {
+            let path = unquote(&word.text);
+            let expanded = expand_path(&path);
+            CdpathEntry {
+                exists: exists(&expanded),
+                portable: is_portable(&path, shared),
+                expanded,
+                path,
+                shared,
+                file: relative.clone(),
+                line: index + 1,
+            }
+        })
+        .collect())
+}
+
+/// Named directories across the managed files; `exists` flags targets missing on this machine
+#[tauri::command]
+pub fn list_named_dirs() -> Result<Vec<NamedDir>, String> {
+    let mut dirs = Vec::new();
+    for file in config_files() {
+        dirs.extend(named_dirs_in(&file)?);
+    }
+    Ok(dirs)
+}
+
+/// cdpath directories across the managed files, in the order cd searches them
+#[tauri::command]
+pub fn list_cdpath() -> Result<Vec<CdpathEntry>, String> {
+    let mut entries = Vec::new();
+    for file in config_files() {
+        entries.extend(cdpath_in(&file)?);
+    }
+    Ok(entries)
+}
+
+fn write_lines(path: &Path, lines: &[String]) -> Result<(), String> {
+    write_config(path, &(lines.join("\n") + "\n"))
+}
+
+fn read_lines(path: &Path) -> Result<Vec<String>, String> {
+    Ok(read_config(path)?
+        .unwrap_or_default()
+        .lines()
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// Insert `line` after the last line matching `is_related`, or at the end under `header`
+fn insert_line(lines: &mut Vec<String>, line: String, header: &str, is_related: impl Fn(&str) -> bool) {
+    if let Some(index) = lines.iter().rposition(|l| is_related(l)) {
+        lines.insert(index + 1, line);
+        return;
+    }
+    if lines.last().map(|l| !l.trim().is_empty()).unwrap_or(false) {
+        lines.push(String::new());
+    }
+    lines.push(header.to_string());
+    lines.push(line);
+}
+
+const NAMED_DIRS_HEADER: &str = "# Named directories (cd ~name)";
+const CDPATH_HEADER: &str = "# Directories searched by cd";
+
+/// Remove a line, and the header `insert_line` wrote above it once nothing follows the header
+fn remove_line(lines: &mut Vec<String>, index: usize) {
+    lines.remove(index);
+
+    let orphaned = index > 0
+        && (lines[index - 1] == NAMED_DIRS_HEADER || lines[index - 1] == CDPATH_HEADER)
+        && lines
+            .get(index)
+            .map(|next| !next.trim_start().starts_with("hash -d ") && !next.trim_start().starts_with("cdpath"))
+            .unwrap_or(true);
+    if orphaned {
+        lines.remove(index - 1);
+        if index > 1 && lines[index - 2].trim().is_empty() {
+            lines.remove(index - 2);
+        }
+    }
+}
+
+/// Remove `start..end` from a line, with the whitespace before it
+fn cut_word(line: &str, start: usize, end: usize) -> String {
+    let before = line[..start].trim_end_matches([' ', '\t']);
+    if before.ends_with('(') {
+        format!("{}{}", before, line[end..].trim_start_matches([' ', '\t']))
+    } else {
+        format!("{}{}", before, &line[end..])
+    }
+}
+
+fn find_named_dir(name: &str, shared: bool) -> Result<NamedDir, String> {
+    list_named_dirs()?
+        .into_iter()
+        .rev()
+        .find(|dir| dir.name == name && dir.shared == shared)
+        .ok_or_else(|| format!("Named directory '{}' not found", name))
+}
+
+fn hash_line(name: &str, path: &str) -> Result<String, String> {
+    Ok(format!("hash -d {}={}", name, quoted_path(path)?))
+}
+
+#[tauri::command]
+pub fn add_named_dir(name: String, path: String, shared: bool) -> Result<NamedDir, String> {
+    validate_name(&name)?;
+    let line = hash_line(&name, &path)?;
+    if list_named_dirs()?.iter().any(|dir| dir.name == name && dir.shared == shared) {
+        return Err(format!("Named directory '{}' already exists", name));
+    }
+
+    let file = target_file(shared);
+    let mut lines = read_lines(&file)?;
+    insert_line(&mut lines, line, NAMED_DIRS_HEADER, |line| {
+        line.trim_start().starts_with("hash -d ")
+    });
+    write_lines(&file, &lines)?;
+
+    find_named_dir(&name, shared)
+}
+
+/// Remove one `name=path` word, dropping the line when it was the only one
+fn remove_hash_entry(dir: &NamedDir) -> Result<(), String> {
+    let file = get_home_dir().join(&dir.file);
+    let mut lines = read_lines(&file)?;
+    let index = dir.line - 1;
+
+    let entries = parse_hash_line(&lines[index]);
+    let Some((word, _, _)) = entries.iter().find(|(_, name, _)| *name == dir.name) else {
+        return Err(format!("Named directory '{}' not found", dir.name));
+    };
+    if entries.len() == 1 {
+        remove_line(&mut lines, index);
+    } else {
+        lines[index] = cut_word(&lines[index], word.start, word.end);
+    }
+
+    write_lines(&file, &lines)
+}
+
+/// Rename or repoint a named directory in place
+#[tauri::command]
+pub fn update_named_dir(name: String, new_name: String, path: String, shared: bool) -> Result<NamedDir, String> {
+    validate_name(&new_name)?;
+    let quoted = quoted_path(&path)?;
+    let dir = find_named_dir(&name, shared)?;
+    if new_name != name && list_named_dirs()?.iter().any(|d| d.name == new_name && d.shared == shared) {
+        return Err(format!("Named directory '{}' already exists", new_name));
+    }
+
+    let file = get_home_dir().join(&dir.file);
+    let mut lines = read_lines(&file)?;
+    let index = dir.line - 1;
+    let entries = parse_hash_line(&lines[index]);
+    let (word, _, _) = entries
+        .iter()
+        .find(|(_, n, _)| *n == name)
+        .ok_or_else(|| format!("Named directory '{}' not found", name))?;
+
+    let replacement = format!("{}={}", new_name, quoted);
+    lines[index] = format!("{}{}{}", &lines[index][..word.start], replacement, &lines[index][word.end..]);
+    write_lines(&file, &lines)?;
+
+    find_named_dir(&new_name, shared)
+}
+
+#[tauri::command]
+pub fn remove_named_dir(name: String, shared: bool) -> Result<(), String> {
+    remove_hash_entry(&find_named_dir(&name, shared)?)
+}
+
+/// Move a named directory between config.zsh and .zshrc.local
+#[tauri::command]
+pub fn move_named_dir(name: String, shared: bool) -> Result<NamedDir, String> {
+    let dir = find_named_dir(&name, shared)?;
+    let moved = add_named_dir(name, dir.path.clone(), !shared)?;
+    remove_hash_entry(&dir)?;
+    Ok(moved)
+}
+
+fn find_cdpath(path: &str, shared: bool) -> Result<CdpathEntry, String> {
+    let expanded = expand_path(path);
+    list_cdpath()?
+        .into_iter()
+        .find(|entry| entry.expanded == expanded && entry.shared == shared)
+        .ok_or_else(|| format!("'{}' is not in cdpath", path))
+}
+
+/// Append to the last one-line cdpath array of the file, or add a `cdpath+=(...)` line
+#[tauri::command]
+pub fn add_cdpath(path: String, shared: bool) -> Result<CdpathEntry, String> {
+    let word = quoted_path(&path)?;
+    if find_cdpath(&path, shared).is_ok() {
+        return Err(format!("'{}' is already in cdpath", path));
+    }
+
+    let file = target_file(shared);
+    let mut lines = read_lines(&file)?;
+    let array = Regex::new(r"^\s*cdpath\+?=\(.*\)\s*(#.*)?$").unwrap();
+
+    match lines.iter().rposition(|line| array.is_match(line)) {
+        Some(index) => {
+            let line = &lines[index];
+            let close = split_words(line).last().map(|w| w.end).unwrap_or(line.len());
+            let close = line[..close].rfind(')').unwrap_or(close);
+            let separator = if line[..close].ends_with('(') { "" } else { " " };
+            lines[index] = format!("{}{}{}{}", &line[..close], separator, word, &line[close..]);
+        }
+        None => insert_line(&mut lines, format!("cdpath+=({})", word), CDPATH_HEADER, |_| false),
+    }
+    write_lines(&file, &lines)?;
+
+    find_cdpath(&path, shared)
+}
+
+/// Remove a cdpath directory, dropping array lines left with nothing but `$cdpath`
+fn remove_cdpath_entry(entry: &CdpathEntry) -> Result<(), String> {
+    let file = get_home_dir().join(&entry.file);
+    let content = read_config(&file)?.unwrap_or_default();
+    let mut lines: Vec<String> = content.lines().map(|line| line.to_string()).collect();
+
+    let (index, word) = cdpath_words(&content)
+        .into_iter()
+        .find(|(index, word)| *index + 1 == entry.line && expand_path(&unquote(&word.text)) == entry.expanded)
+        .ok_or_else(|| format!("'{}' is not in cdpath", entry.path))?;
+
+    let line = cut_word(&lines[index], word.start, word.end);
+    let empty = Regex::new(r"^\s*cdpath\+?=\(\s*(\$cdpath|\$\{cdpath\[@\]\})?\s*\)\s*(#.*)?$").unwrap();
+    if empty.is_match(&line) || line.trim().is_empty() {
+        remove_line(&mut lines, index);
+    } else {
+        lines[index] = line;
+    }
+
+    write_lines(&file, &lines)
+}
+
+#[tauri::command]
+pub fn update_cdpath(path: String, new_path: String, shared: bool) -> Result<CdpathEntry, String> {
+    let replacement = quoted_path(&new_path)?;
+    let entry = find_cdpath(&path, shared)?;
+    if find_cdpath(&new_path, shared).is_ok() {
+        return Err(format!("'{}' is already in cdpath", new_path));
+    }
+
+    let file = get_home_dir().join(&entry.file);
+    let content = read_config(&file)?.unwrap_or_default();
+    let mut lines: Vec<String> = content.lines().map(|line| line.to_string()).collect();
+    let (index, word) = cdpath_words(&content)
+        .into_iter()
+        .find(|(index, word)| *index + 1 == entry.line && expand_path(&unquote(&word.text)) == entry.expanded)
+        .ok_or_else(|| format!("'{}' is not in cdpath", path))?;
+
+    lines[index] = format!("{}{}{}", &lines[index][..word.start], replacement, &lines[index][word.end..]);
+    write_lines(&file, &lines)?;
+
+    find_cdpath(&new_path, shared)
+}
+
+#[tauri::command]
+pub fn remove_cdpath(path: String, shared: bool) -> Result<(), String> {
+    remove_cdpath_entry(&find_cdpath(&path, shared)?)
+}
+
+/// Move a cdpath directory between config.zsh and .zshrc.local
+#[tauri::command]
+pub fn move_cdpath(path: String, shared: bool) -> Result<CdpathEntry, String> {
+    let entry = find_cdpath(&path, shared)?;
+    let moved = add_cdpath(entry.path.clone(), !shared)?;
+    remove_cdpath_entry(&entry)?;
+    Ok(moved)
+}
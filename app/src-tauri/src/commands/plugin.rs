@@ -1,8 +1,21 @@
 use serde::{Deserialize, Serialize};
-use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
-use regex::Regex;
+
+use super::plugin_install::{get_custom_plugins_dir, read_plugin_repo};
+use super::plugin_lock::{lock_state, read_lockfile, LockState};
+use super::plugin_managers::{backend_declaring, backend_for_new_plugin, detected_backends, get_oh_my_zsh_dir};
+use super::plugin_managers::oh_my_zsh::{order_warnings, OhMyZshBackend};
+use super::plugin_managers::PluginBackend;
+
+/// Plugins with curated metadata in `get_plugin_info`
+pub(crate) const POPULAR_PLUGINS: &[&str] = &[
+    "git", "zsh-autosuggestions", "zsh-syntax-highlighting",
+    "alias-tips", "fzf", "fzf-tab",
+    "docker", "docker-compose", "kubectl", "npm", "node",
+    "rust", "python", "sudo", "web-search", "history",
+    "colored-man-pages", "command-not-found",
+];
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Plugin {
@@ -12,7 +25,16 @@ pub struct Plugin {
     pub description: Option<String>,
     pub repository: Option<String>,
     pub manager: PluginManager,
+    /// Listed in the versioned ~/.zsh/plugins.zsh rather than ~/.zshrc.local
+    pub shared: bool,
     pub install_command: Option<String>,
+    /// Nearest tag of a plugin installed with git
+    pub version: Option<String>,
+    pub commit: Option<String>,
+    /// The plugin's remote had new commits at the last update check
+    pub update_available: bool,
+    /// How the installed commit compares with ~/.zsh/plugins.lock; None when not locked
+    pub lock_state: Option<LockState>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -20,52 +42,127 @@ pub struct Plugin {
 pub enum PluginManager {
     OhMyZsh,
     Zinit,
+    Antidote,
+    Antigen,
+    Zplug,
+    Sheldon,
     Custom,
 }
 
-fn get_home_dir() -> PathBuf {
-    dirs::home_dir().expect("Could not find home directory")
+pub(crate) fn get_oh_my_zsh_plugins_dir() -> PathBuf {
+    get_oh_my_zsh_dir().join("plugins")
 }
 
-fn get_zshrc_local_path() -> PathBuf {
-    get_home_dir().join(".zshrc.local")
+/// A plugin loaded in an order that breaks it or another plugin
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PluginOrderWarning {
+    pub plugin: String,
+    pub message: String,
 }
 
-fn get_oh_my_zsh_plugins_dir() -> PathBuf {
-    get_home_dir().join(".oh-my-zsh/plugins")
+/// A name in the `plugins=(...)` array and its byte range in the file
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ArrayEntry {
+    pub name: String,
+    pub start: usize,
+    pub end: usize,
 }
 
-fn get_oh_my_zsh_custom_plugins_dir() -> PathBuf {
-    get_home_dir().join(".oh-my-zsh/custom/plugins")
+/// The `plugins=(...)` array: byte offsets of the assignment, just after `(` and of the closing `)`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PluginsArray {
+    pub start: usize,
+    pub open: usize,
+    pub close: usize,
+    pub entries: Vec<ArrayEntry>,
+    /// Number of entries loaded before the array's previous value: 0 for `plugins+=(...)`,
+    /// the position of `$plugins` in `plugins=(... $plugins ...)`, None when it is replaced
+    pub existing_at: Option<usize>,
 }
 
-/// Parse the plugins array from .zshrc.local
-fn parse_plugins_from_file(content: &str) -> Vec<String> {
-    let re = Regex::new(r"plugins=\(\s*([^)]*)\s*\)").unwrap();
-
-    if let Some(captures) = re.captures(content) {
-        if let Some(plugins_str) = captures.get(1) {
-            return plugins_str
-                .as_str()
-                .split_whitespace()
-                .map(|s| s.to_string())
-                .collect();
+/// Locate the first `plugins=(...)` (or `plugins+=(...)`) assignment that starts a line and split
+/// it into entries. Comments inside the array are skipped, so a `)` in `git # (core)` does not end it.
+/// Parameter expansions such as `$plugins` are not entries.
+pub(crate) fn find_plugins_array(content: &str) -> Option<PluginsArray> {
+    let mut offset = 0;
+    let (start, open) = content.split_inclusive('\n').find_map(|line| {
+        let trimmed = line.trim_start();
+        let start = offset + (line.len() - trimmed.len());
+        offset += line.len();
+        ["plugins=(", "plugins+=("]
+            .iter()
+            .find(|assignment| trimmed.starts_with(**assignment))
+            .map(|assignment| (start, start + assignment.len()))
+    })?;
+
+    let mut entries = Vec::new();
+    let mut existing_at = (content[start..open].starts_with("plugins+=")).then_some(0);
+    let mut word_start: Option<usize> = None;
+    let mut quote: Option<char> = None;
+    let mut in_comment = false;
+
+    let mut finish_word = |entries: &mut Vec<ArrayEntry>, start: &mut Option<usize>, end: usize| {
+        if let Some(start) = start.take() {
+            let name = content[start..end].trim_matches(|c| c == '"' || c == '\'');
+            if ["$plugins", "${plugins}", "${plugins[@]}"].contains(&name) {
+                existing_at = Some(entries.len());
+            } else if !name.is_empty() && !name.starts_with('$') {
+                entries.push(ArrayEntry { name: name.to_string(), start, end });
+            }
+        }
+    };
+
+    for (index, c) in content[open..].char_indices() {
+        let position = open + index;
+
+        if in_comment {
+            in_comment = c != '\n';
+            continue;
+        }
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+
+        match c {
+            '\'' | '"' => {
+                quote = Some(c);
+                word_start.get_or_insert(position);
+            }
+            '#' if word_start.is_none() => in_comment = true,
+            ')' => {
+                finish_word(&mut entries, &mut word_start, position);
+                return Some(PluginsArray { start, open, close: position, entries, existing_at });
+            }
+            c if c.is_whitespace() => finish_word(&mut entries, &mut word_start, position),
+            _ => {
+                word_start.get_or_insert(position);
+            }
         }
     }
 
-    Vec::new()
+    None
+}
+
+/// Parse the plugins array from .zshrc.local
+pub(crate) fn parse_plugins_from_file(content: &str) -> Vec<String> {
+    find_plugins_array(content)
+        .map(|array| array.entries.into_iter().map(|entry| entry.name).collect())
+        .unwrap_or_default()
 }
 
 /// Check if a plugin is installed in oh-my-zsh
 fn is_plugin_installed(plugin_name: &str) -> bool {
     let builtin_path = get_oh_my_zsh_plugins_dir().join(plugin_name);
-    let custom_path = get_oh_my_zsh_custom_plugins_dir().join(plugin_name);
+    let custom_path = get_custom_plugins_dir().join(plugin_name);
 
     builtin_path.exists() || custom_path.exists()
 }
 
-/// Get popular plugin information
-fn get_plugin_info(name: &str) -> (Option<String>, Option<String>, Option<String>) {
+/// Curated description, repository and install instructions of well-known plugins
+pub(crate) fn get_plugin_info(name: &str) -> (Option<String>, Option<String>, Option<String>) {
     match name {
         "git" => (
             Some("Git aliases and functions".to_string()),
@@ -161,72 +258,67 @@ fn get_plugin_info(name: &str) -> (Option<String>, Option<String>, Option<String
     }
 }
 
+/// Plugin managers detected on this machine
 #[tauri::command]
-pub fn get_plugins() -> Result<Vec<Plugin>, String> {
-    let file_path = get_zshrc_local_path();
-
-    if !file_path.exists() {
-        return Ok(Vec::new());
-    }
-
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read .zshrc.local: {}", e))?;
+pub fn get_plugin_managers() -> Result<Vec<PluginManager>, String> {
+    Ok(detected_backends().iter().map(|backend| backend.manager()).collect())
+}
 
-    let enabled_plugins = parse_plugins_from_file(&content);
+/// Plugins in the oh-my-zsh array that are loaded in the wrong order,
+/// e.g. zsh-syntax-highlighting before other plugins
+#[tauri::command]
+pub fn get_plugin_order_warnings() -> Result<Vec<PluginOrderWarning>, String> {
+    let names: Vec<String> = OhMyZshBackend.list()?.into_iter().map(|plugin| plugin.name).collect();
+    Ok(order_warnings(&names))
+}
 
-    let mut plugins: Vec<Plugin> = enabled_plugins
-        .iter()
-        .map(|name| {
-            let installed = is_plugin_installed(name);
-            let (description, repository, install_command) = get_plugin_info(name);
-
-            Plugin {
-                name: name.clone(),
-                enabled: true,
-                installed,
-                description,
-                repository,
-                manager: PluginManager::OhMyZsh,
-                install_command,
-            }
-        })
-        .collect();
+/// Plugins of every plugin manager used on this machine
+#[tauri::command]
+pub fn get_plugins() -> Result<Vec<Plugin>, String> {
+    let mut plugins = Vec::new();
+    for backend in detected_backends() {
+        plugins.extend(backend.list()?);
+    }
 
     plugins.sort_by(|a, b| a.name.cmp(&b.name));
 
     Ok(plugins)
 }
 
+/// An oh-my-zsh plugin with its description, and its version when it is a git clone
+pub(crate) fn oh_my_zsh_plugin(name: &str, enabled: bool) -> Plugin {
+    let (description, repository, install_command) = get_plugin_info(name);
+    let repo = read_plugin_repo(&get_custom_plugins_dir().join(name)).unwrap_or_default();
+    let lock_state = read_lockfile()
+        .ok()
+        .flatten()
+        .and_then(|lock| lock_state(&lock, name, repo.commit.as_deref()));
+
+    Plugin {
+        name: name.to_string(),
+        enabled,
+        installed: is_plugin_installed(name),
+        description,
+        repository: repository.or(repo.remote_url),
+        manager: PluginManager::OhMyZsh,
+        shared: false,
+        install_command,
+        version: repo.version,
+        commit: repo.commit,
+        update_available: repo.update_available,
+        lock_state,
+    }
+}
+
 #[tauri::command]
 pub fn get_popular_plugins() -> Result<Vec<Plugin>, String> {
     let enabled_plugins = get_plugins()?;
     let enabled_names: Vec<String> = enabled_plugins.iter().map(|p| p.name.clone()).collect();
 
-    let popular = vec![
-        "git", "zsh-autosuggestions", "zsh-syntax-highlighting",
-        "alias-tips", "fzf", "fzf-tab",
-        "docker", "docker-compose", "kubectl", "npm", "node",
-        "rust", "python", "sudo", "web-search", "history",
-        "colored-man-pages", "command-not-found",
-    ];
-
-    let mut plugins: Vec<Plugin> = popular
+    let mut plugins: Vec<Plugin> = POPULAR_PLUGINS
         .iter()
         .filter(|name| !enabled_names.contains(&name.to_string()))
-        .map(|name| {
-            let installed = is_plugin_installed(name);
-            let (description, repository, install_command) = get_plugin_info(name);
-
-            Plugin {
-                name: name.to_string(),
-                enabled: false,
-                installed,
-                description,
-                repository,
-                manager: PluginManager::OhMyZsh,
-                install_command,
-            }
-        })
+        .map(|name| oh_my_zsh_plugin(name, false))
         .collect();
 
     plugins.sort_by(|a, b| a.name.cmp(&b.name));
@@ -234,76 +326,38 @@ pub fn get_popular_plugins() -> Result<Vec<Plugin>, String> {
     Ok(plugins)
 }
 
+/// Shared plugins go to ~/.zsh/plugins.zsh, which only the oh-my-zsh array reads
 #[tauri::command]
-pub fn add_plugin(plugin_name: String) -> Result<(), String> {
-    let file_path = get_zshrc_local_path();
-
-    if !file_path.exists() {
-        return Err("~/.zshrc.local does not exist".to_string());
+pub fn add_plugin(plugin_name: String, shared: bool) -> Result<(), String> {
+    if shared {
+        return OhMyZshBackend.add_to(&plugin_name, true);
     }
-
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read .zshrc.local: {}", e))?;
-
-    let mut plugins = parse_plugins_from_file(&content);
-
-    if plugins.contains(&plugin_name) {
-        return Err(format!("Plugin '{}' is already enabled", plugin_name));
-    }
-
-    plugins.push(plugin_name);
-    plugins.sort();
-
-    let new_plugins_str = plugins.join("\n  ");
-    let new_plugins_block = format!("plugins=(\n  {}\n)", new_plugins_str);
-
-    let re = Regex::new(r"plugins=\(\s*([^)]*)\s*\)").unwrap();
-    let new_content = re.replace(&content, new_plugins_block.as_str()).to_string();
-
-    fs::write(&file_path, new_content)
-        .map_err(|e| format!("Failed to write .zshrc.local: {}", e))?;
-
-    Ok(())
+    backend_for_new_plugin(&plugin_name).add(&plugin_name)
 }
 
 #[tauri::command]
-pub fn remove_plugin(plugin_name: String) -> Result<(), String> {
-    let file_path = get_zshrc_local_path();
-
-    if !file_path.exists() {
-        return Err("~/.zshrc.local does not exist".to_string());
+pub fn remove_plugin(plugin_name: String, shared: bool) -> Result<(), String> {
+    if shared {
+        return OhMyZshBackend.remove_from(&plugin_name, true);
     }
 
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read .zshrc.local: {}", e))?;
-
-    let mut plugins = parse_plugins_from_file(&content);
-
-    if !plugins.contains(&plugin_name) {
-        return Err(format!("Plugin '{}' is not enabled", plugin_name));
+    // The oh-my-zsh backend also lists shared plugins, which must stay untouched here
+    let backend = backend_declaring(&plugin_name)?;
+    if backend.manager() == PluginManager::OhMyZsh {
+        return OhMyZshBackend.remove_from(&plugin_name, false);
     }
+    backend.remove(&plugin_name)
+}
 
-    plugins.retain(|p| p != &plugin_name);
-
-    let new_plugins_str = if plugins.is_empty() {
-        String::new()
-    } else {
-        plugins.join("\n  ")
-    };
-
-    let new_plugins_block = if new_plugins_str.is_empty() {
-        "plugins=()".to_string()
-    } else {
-        format!("plugins=(\n  {}\n)", new_plugins_str)
-    };
-
-    let re = Regex::new(r"plugins=\(\s*([^)]*)\s*\)").unwrap();
-    let new_content = re.replace(&content, new_plugins_block.as_str()).to_string();
-
-    fs::write(&file_path, new_content)
-        .map_err(|e| format!("Failed to write .zshrc.local: {}", e))?;
-
-    Ok(())
+/// Enable or disable a plugin without forgetting it where the manager allows: line-based
+/// declarations are commented out, oh-my-zsh plugins are added to or removed from the array
+#[tauri::command]
+pub fn set_plugin_enabled(plugin_name: String, enabled: bool) -> Result<(), String> {
+    match backend_declaring(&plugin_name) {
+        Ok(backend) => backend.set_enabled(&plugin_name, enabled),
+        Err(_) if enabled => add_plugin(plugin_name, false),
+        Err(e) => Err(e),
+    }
 }
 
 /// Open a URL in the default browser (WSL-compatible)
@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+use regex::Regex;
+
+use super::plugin::{get_oh_my_zsh_plugins_dir, get_plugin_info, get_plugins, POPULAR_PLUGINS};
+use super::plugin_install::get_custom_plugins_dir;
+
+/// Longest description taken from a README
+const MAX_DESCRIPTION_LEN: usize = 240;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CatalogEntry {
+    pub name: String,
+    pub description: Option<String>,
+    /// Ships with oh-my-zsh rather than living in ~/.oh-my-zsh/custom/plugins
+    pub builtin: bool,
+    pub installed: bool,
+    pub enabled: bool,
+    pub repository: Option<String>,
+    pub install_command: Option<String>,
+}
+
+/// An indexed plugin with the lowercased text searched by `search_plugins`
+#[derive(Debug, Clone)]
+struct IndexedPlugin {
+    entry: CatalogEntry,
+    text: String,
+}
+
+/// The index and the modification times of the directories it was built from
+struct Catalog {
+    stamps: Vec<Option<SystemTime>>,
+    plugins: Vec<IndexedPlugin>,
+}
+
+fn catalog() -> &'static Mutex<Option<Catalog>> {
+    static CATALOG: OnceLock<Mutex<Option<Catalog>>> = OnceLock::new();
+    CATALOG.get_or_init(|| Mutex::new(None))
+}
+
+fn find_readme(dir: &Path) -> Option<PathBuf> {
+    let mut candidates: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_lowercase().starts_with("readme"))
+                    .unwrap_or(false)
+        })
+        .collect();
+
+    // Prefer README.md over translations such as README.zh-CN.md
+    candidates.sort_by_key(|path| path.file_name().map(|name| name.len()).unwrap_or(0));
+    candidates.into_iter().next()
+}
+
+/// Plain text of a markdown line: links and images reduced to their text, emphasis and code marks dropped
+fn strip_markdown(line: &str) -> String {
+    static PATTERNS: OnceLock<(Regex, Regex, Regex)> = OnceLock::new();
+    let (image, link, html) = PATTERNS.get_or_init(|| {
+        (
+            Regex::new(r"!\[([^\]]*)\]\([^)]*\)").unwrap(),
+            Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap(),
+            Regex::new(r"<[^>]+>").unwrap(),
+        )
+    });
+
+    let text = image.replace_all(line, "");
+    let text = link.replace_all(&text, "$1");
+    let text = html.replace_all(&text, "");
+    text.replace("**", "").replace('`', "").trim().to_string()
+}
+
+/// The first paragraph of prose in a README, skipping headings, badges, HTML and code blocks
+pub(crate) fn readme_description(content: &str) -> Option<String> {
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut in_code = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            if !paragraph.is_empty() {
+                break;
+            }
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+
+        let is_prose = !trimmed.is_empty()
+            && !trimmed.starts_with('#')
+            && !trimmed.starts_with('<')
+            && !trimmed.starts_with("[!")
+            && !trimmed.starts_with("![")
+            && !trimmed.starts_with('|')
+            && !trimmed.starts_with("---")
+            && !trimmed.starts_with("===");
+
+        if is_prose {
+            let text = strip_markdown(trimmed);
+            if !text.is_empty() {
+                paragraph.push(text);
+            }
+        } else if !paragraph.is_empty() {
+            break;
+        }
+    }
+
+    let description = paragraph.join(" ");
+    if description.is_empty() {
+        return None;
+    }
+    if description.chars().count() <= MAX_DESCRIPTION_LEN {
+        return Some(description);
+    }
+
+    let truncated: String = description.chars().take(MAX_DESCRIPTION_LEN).collect();
+    let cut = truncated.rfind(' ').unwrap_or(truncated.len());
+    Some(format!("{}…", truncated[..cut].trim_end_matches([',', '.', ';', ':'])))
+}
+
+/// Index every plugin directory; curated metadata wins over the README for descriptions
+fn scan_plugins_dir(dir: &Path, builtin: bool) -> Vec<IndexedPlugin> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || name == "example" {
+                return None;
+            }
+
+            let readme = find_readme(&entry.path()).and_then(|path| fs::read_to_string(path).ok());
+            let (curated, repository, install_command) = get_plugin_info(&name);
+            let description = curated.or_else(|| readme.as_deref().and_then(readme_description));
+
+            let text = format!(
+                "{}\n{}\n{}",
+                name,
+                description.as_deref().unwrap_or_default(),
+                readme.as_deref().unwrap_or_default()
+            )
+            .to_lowercase();
+
+            Some(IndexedPlugin {
+                entry: CatalogEntry {
+                    name,
+                    description,
+                    builtin,
+                    installed: true,
+                    enabled: false,
+                    repository,
+                    install_command,
+                },
+                text,
+            })
+        })
+        .collect()
+}
+
+fn directory_stamps() -> Vec<Option<SystemTime>> {
+    [get_oh_my_zsh_plugins_dir(), get_custom_plugins_dir()]
+        .iter()
+        .map(|dir| fs::metadata(dir).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+/// Build the index, custom plugins shadowing built-in ones of the same name as oh-my-zsh does.
+/// Curated plugins that are not installed are listed so they can be discovered and installed.
+fn build_catalog() -> Vec<IndexedPlugin> {
+    let mut plugins = scan_plugins_dir(&get_custom_plugins_dir(), false);
+
+    for plugin in scan_plugins_dir(&get_oh_my_zsh_plugins_dir(), true) {
+        if !plugins.iter().any(|p| p.entry.name == plugin.entry.name) {
+            plugins.push(plugin);
+        }
+    }
+
+    for name in POPULAR_PLUGINS {
+        if plugins.iter().any(|p| p.entry.name == *name) {
+            continue;
+        }
+        let (description, repository, install_command) = get_plugin_info(name);
+        plugins.push(IndexedPlugin {
+            text: format!("{}\n{}", name, description.as_deref().unwrap_or_default()).to_lowercase(),
+            entry: CatalogEntry {
+                name: name.to_string(),
+                description,
+                builtin: false,
+                installed: false,
+                enabled: false,
+                repository,
+                install_command,
+            },
+        });
+    }
+
+    plugins.sort_by(|a, b| a.entry.name.cmp(&b.entry.name));
+    plugins
+}
+
+/// The cached index, rebuilt when a plugin is added to or removed from either directory
+fn indexed_plugins() -> Result<Vec<IndexedPlugin>, String> {
+    let stamps = directory_stamps();
+    let mut guard = catalog().lock().map_err(|e| format!("Failed to lock plugin catalog: {}", e))?;
+
+    let stale = guard.as_ref().map(|c| c.stamps != stamps).unwrap_or(true);
+    if stale {
+        *guard = Some(Catalog {
+            stamps,
+            plugins: build_catalog(),
+        });
+    }
+
+    let mut plugins = guard.as_ref().map(|c| c.plugins.clone()).unwrap_or_default();
+    drop(guard);
+
+    let enabled: Vec<String> = get_plugins()?.into_iter().filter(|p| p.enabled).map(|p| p.name).collect();
+    for plugin in &mut plugins {
+        plugin.entry.enabled = enabled.contains(&plugin.entry.name);
+    }
+
+    Ok(plugins)
+}
+
+/// Relevance of a plugin for the query words, or None when a word does not occur at all
+fn score(plugin: &IndexedPlugin, words: &[String]) -> Option<u32> {
+    let name = plugin.entry.name.to_lowercase();
+    let description = plugin.entry.description.as_deref().unwrap_or_default().to_lowercase();
+
+    words.iter().try_fold(0, |total, word| {
+        let points = if name == *word {
+            100
+        } else if name.contains(word.as_str()) {
+            40
+        } else if description.contains(word.as_str()) {
+            15
+        } else if plugin.text.contains(word.as_str()) {
+            1 + plugin.text.matches(word.as_str()).count().min(9) as u32
+        } else {
+            return None;
+        };
+        Some(total + points)
+    })
+}
+
+/// Every plugin in ~/.oh-my-zsh/plugins and ~/.oh-my-zsh/custom/plugins, plus the curated ones
+#[tauri::command]
+pub fn get_plugin_catalog() -> Result<Vec<CatalogEntry>, String> {
+    Ok(indexed_plugins()?.into_iter().map(|plugin| plugin.entry).collect())
+}
+
+/// Search plugin names, descriptions and READMEs; every word of the query must match
+#[tauri::command]
+pub fn search_plugins(query: String, limit: Option<usize>) -> Result<Vec<CatalogEntry>, String> {
+    let words: Vec<String> = query.split_whitespace().map(|word| word.to_lowercase()).collect();
+    let mut results: Vec<(u32, CatalogEntry)> = indexed_plugins()?
+        .into_iter()
+        .filter_map(|plugin| score(&plugin, &words).map(|score| (score, plugin.entry)))
+        .collect();
+
+    results.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+
+    Ok(results
+        .into_iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|(_, entry)| entry)
+        .collect())
+}
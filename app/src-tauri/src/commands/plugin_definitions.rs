@@ -0,0 +1,259 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use regex::Regex;
+
+use super::alias::{list_aliases, parse_alias_line};
+use super::function::list_functions;
+use super::plugin::get_oh_my_zsh_plugins_dir;
+use super::plugin_install::get_custom_plugins_dir;
+use super::plugin_managers::oh_my_zsh::OhMyZshBackend;
+use super::plugin_managers::{relative_to_home, PluginBackend};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DefinitionKind {
+    Alias,
+    Function,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PluginAlias {
+    pub name: String,
+    pub command: String,
+}
+
+/// What an enabled plugin's `<name>.plugin.zsh` defines at load time
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PluginDefinitions {
+    pub plugin: String,
+    /// The plugin file, relative to the home directory
+    pub file: Option<String>,
+    pub aliases: Vec<PluginAlias>,
+    pub functions: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Definition {
+    pub kind: DefinitionKind,
+    /// The plugin defining the name, or None for our own alias and function files
+    pub plugin: Option<String>,
+    /// File the definition comes from, relative to the home directory
+    pub file: String,
+    /// Alias command; None for functions
+    pub command: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DefinitionConflict {
+    pub name: String,
+    /// Every definition of the name, in load order
+    pub definitions: Vec<Definition>,
+    /// The definition in effect in an interactive shell
+    pub winner: Definition,
+}
+
+/// Start of a function definition: `name() {`, `function name {` or `function name() {`
+fn function_start_regex() -> Regex {
+    Regex::new(r"^(?:function\s+([^\s(){}]+)\s*(?:\(\s*\))?|([^\s(){}=$]+)\s*\(\s*\))\s*(?:\{|$)").unwrap()
+}
+
+/// Aliases and functions defined at the top level of a plugin file.
+/// Aliases inside function bodies only exist once the function runs, so they are skipped.
+pub(crate) fn parse_plugin_source(content: &str) -> (Vec<PluginAlias>, Vec<String>) {
+    let function_start = function_start_regex();
+    let flags = Regex::new(r"^alias\s+(?:-[a-zA-Z]+\s+)+").unwrap();
+    let mut aliases = Vec::new();
+    let mut functions = Vec::new();
+    // Brace depth of the function body being skipped
+    let mut depth: Option<i32> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(current) = depth.as_mut() {
+            *current += brace_delta(trimmed);
+            if *current <= 0 && trimmed.contains('}') {
+                depth = None;
+            }
+            continue;
+        }
+
+        if let Some(captures) = function_start.captures(trimmed) {
+            let name = captures.get(1).or_else(|| captures.get(2)).map(|m| m.as_str().to_string());
+            if let Some(name) = name {
+                if !functions.contains(&name) {
+                    functions.push(name);
+                }
+            }
+            let delta = brace_delta(trimmed);
+            if delta > 0 || !trimmed.contains('{') {
+                depth = Some(delta);
+            }
+            continue;
+        }
+
+        // `alias -g G='| grep'` declares `G`
+        let line = flags.replace(trimmed, "alias ");
+        if let Some(alias) = parse_alias_line(&line) {
+            if alias.name.chars().all(|c| !c.is_whitespace() && c != '$') {
+                aliases.retain(|a: &PluginAlias| a.name != alias.name);
+                aliases.push(PluginAlias {
+                    name: alias.name,
+                    command: alias.command,
+                });
+            }
+        }
+    }
+
+    (aliases, functions)
+}
+
+/// Opening minus closing braces outside of quotes
+fn brace_delta(line: &str) -> i32 {
+    let mut delta = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+
+    for c in line.chars() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match (quote, c) {
+            (_, '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '{') => delta += 1,
+            (None, '}') => delta -= 1,
+            _ => {}
+        }
+    }
+
+    delta
+}
+
+/// The file oh-my-zsh sources for a plugin; custom plugins shadow built-in ones
+fn plugin_file(name: &str) -> Option<PathBuf> {
+    [get_custom_plugins_dir(), get_oh_my_zsh_plugins_dir()]
+        .iter()
+        .map(|dir| dir.join(name).join(format!("{}.plugin.zsh", name)))
+        .find(|path| path.exists())
+}
+
+/// Aliases and functions of every enabled oh-my-zsh plugin, in load order
+#[tauri::command]
+pub fn get_plugin_definitions() -> Result<Vec<PluginDefinitions>, String> {
+    let plugins = OhMyZshBackend.list()?;
+
+    Ok(plugins
+        .iter()
+        .map(|plugin| {
+            let file = plugin_file(&plugin.name);
+            let content = file.as_ref().and_then(|path| fs::read_to_string(path).ok()).unwrap_or_default();
+            let (aliases, functions) = parse_plugin_source(&content);
+
+            PluginDefinitions {
+                plugin: plugin.name.clone(),
+                file: file.as_deref().map(relative_to_home),
+                aliases,
+                functions,
+            }
+        })
+        .collect())
+}
+
+/// The definition that is in effect: aliases are expanded before functions are looked up,
+/// otherwise the last definition loaded wins. None when there are no definitions.
+fn effective_definition(definitions: &[Definition]) -> Option<Definition> {
+    definitions
+        .iter()
+        .rev()
+        .find(|definition| definition.kind == DefinitionKind::Alias)
+        .or_else(|| definitions.last())
+        .cloned()
+}
+
+/// Names defined both by an enabled plugin and in our alias or function files.
+/// Plugins load from ~/.zshrc.local before ~/.zsh/aliases*.zsh and ~/.zsh/functions*.zsh.
+#[tauri::command]
+pub fn get_definition_conflicts() -> Result<Vec<DefinitionConflict>, String> {
+    let mut definitions: Vec<(String, Definition)> = Vec::new();
+
+    for plugin in get_plugin_definitions()? {
+        let file = plugin.file.clone().unwrap_or_default();
+        for alias in plugin.aliases {
+            definitions.push((alias.name, Definition {
+                kind: DefinitionKind::Alias,
+                plugin: Some(plugin.plugin.clone()),
+                file: file.clone(),
+                command: Some(alias.command),
+            }));
+        }
+        for function in plugin.functions {
+            definitions.push((function, Definition {
+                kind: DefinitionKind::Function,
+                plugin: Some(plugin.plugin.clone()),
+                file: file.clone(),
+                command: None,
+            }));
+        }
+    }
+
+    // Same order as ~/.zshrc sources them
+    for shared in [true, false] {
+        let file = if shared { ".zsh/aliases.zsh" } else { ".zsh/aliases.local.zsh" };
+        for alias in list_aliases(shared)? {
+            definitions.push((alias.name, Definition {
+                kind: DefinitionKind::Alias,
+                plugin: None,
+                file: file.to_string(),
+                command: Some(alias.command),
+            }));
+        }
+    }
+    for shared in [true, false] {
+        let file = if shared { ".zsh/functions.zsh" } else { ".zsh/functions.local.zsh" };
+        for function in list_functions(shared)? {
+            definitions.push((function.name, Definition {
+                kind: DefinitionKind::Function,
+                plugin: None,
+                file: file.to_string(),
+                command: None,
+            }));
+        }
+    }
+
+    let mut names: Vec<String> = definitions
+        .iter()
+        .filter(|(_, definition)| definition.plugin.is_none())
+        .map(|(name, _)| name.clone())
+        .collect();
+    names.sort();
+    names.dedup();
+
+    Ok(names
+        .into_iter()
+        .filter_map(|name| {
+            let found: Vec<Definition> = definitions
+                .iter()
+                .filter(|(n, _)| *n == name)
+                .map(|(_, definition)| definition.clone())
+                .collect();
+
+            if !found.iter().any(|definition| definition.plugin.is_some()) {
+                return None;
+            }
+
+            Some(DefinitionConflict {
+                winner: effective_definition(&found)?,
+                name,
+                definitions: found,
+            })
+        })
+        .collect())
+}
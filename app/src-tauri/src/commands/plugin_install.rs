@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use super::git::run_git_command_in;
+use super::plugin::{oh_my_zsh_plugin, Plugin};
+use super::plugin_managers::oh_my_zsh::OhMyZshBackend;
+use super::plugin_managers::{get_oh_my_zsh_dir, PluginBackend};
+use super::progress::{run_git_streaming, GitTask};
+use super::setup::expand_url;
+
+/// Version details of a plugin installed as a git clone
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PluginRepo {
+    pub commit: Option<String>,
+    /// Nearest tag, e.g. "v0.7.0" or "v0.7.0-3-gabc1234"
+    pub version: Option<String>,
+    pub remote_url: Option<String>,
+    /// The upstream branch had new commits at the last fetch
+    pub update_available: bool,
+}
+
+pub(crate) fn get_custom_plugins_dir() -> PathBuf {
+    get_oh_my_zsh_dir().join("custom/plugins")
+}
+
+/// Read the state of a git clone; None when `dir` is not one
+pub(crate) fn read_plugin_repo(dir: &Path) -> Option<PluginRepo> {
+    if !dir.join(".git").exists() {
+        return None;
+    }
+
+    let query = |args: &[&str]| {
+        run_git_command_in(dir, args)
+            .ok()
+            .map(|output| output.trim().to_string())
+            .filter(|output| !output.is_empty())
+    };
+
+    let update_available = query(&["rev-list", "--count", "HEAD..@{upstream}"])
+        .and_then(|count| count.parse::<u32>().ok())
+        .map(|count| count > 0)
+        .unwrap_or(false);
+
+    Some(PluginRepo {
+        commit: query(&["rev-parse", "HEAD"]),
+        version: query(&["describe", "--tags"]),
+        remote_url: query(&["remote", "get-url", "origin"]),
+        update_available,
+    })
+}
+
+/// Directory name for a clone of `url`: its last path component without `.git`
+fn name_from_url(url: &str) -> String {
+    url.trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+pub(crate) fn check_plugin_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name == "." || name == ".." || name.starts_with('-') || name.contains('/') {
+        return Err(format!("Invalid plugin name '{}'", name));
+    }
+    Ok(())
+}
+
+/// A plugin directory in the custom plugins directory that is a git clone
+fn custom_plugin_dir(name: &str) -> Result<PathBuf, String> {
+    check_plugin_name(name)?;
+
+    let dir = get_custom_plugins_dir().join(name);
+    if !dir.exists() {
+        return Err(format!("Plugin '{}' is not installed in ~/.oh-my-zsh/custom/plugins", name));
+    }
+    if !dir.join(".git").exists() {
+        return Err(format!("Plugin '{}' was not installed with git", name));
+    }
+    Ok(dir)
+}
+
+/// Clone a plugin into ~/.oh-my-zsh/custom/plugins from a repository URL or local path.
+/// The plugin is installed, not enabled.
+#[tauri::command]
+pub async fn install_plugin(
+    app: AppHandle,
+    url: String,
+    name: Option<String>,
+    operation_id: Option<String>,
+) -> Result<Plugin, String> {
+    let url = expand_url(url.trim());
+    if url.is_empty() || url.starts_with('-') {
+        return Err(format!("Invalid repository URL '{}'", url));
+    }
+
+    let name = name.unwrap_or_else(|| name_from_url(&url));
+    check_plugin_name(&name)?;
+
+    let plugins_dir = get_custom_plugins_dir();
+    if plugins_dir.join(&name).exists() {
+        return Err(format!("Plugin '{}' is already installed", name));
+    }
+    fs::create_dir_all(&plugins_dir)
+        .map_err(|e| format!("Failed to create custom plugins directory: {}", e))?;
+
+    let args = ["clone", "--progress", "--", url.as_str(), name.as_str()];
+    run_git_streaming(&app, operation_id, plugins_dir, &args, None).await?;
+
+    Ok(oh_my_zsh_plugin(&name, false))
+}
+
+/// Fast-forward an installed plugin to its upstream branch
+#[tauri::command]
+pub async fn update_plugin(app: AppHandle, name: String, operation_id: Option<String>) -> Result<Plugin, String> {
+    let dir = custom_plugin_dir(&name)?;
+
+    run_git_streaming(&app, operation_id, dir, &["pull", "--ff-only", "--progress"], None).await?;
+
+    let enabled = OhMyZshBackend.list()?.iter().any(|plugin| plugin.name == name);
+    Ok(oh_my_zsh_plugin(&name, enabled))
+}
+
+/// Fetch every git-installed custom plugin so `update_available` reflects the remotes
+#[tauri::command]
+pub async fn check_plugin_updates(app: AppHandle, operation_id: Option<String>) -> Result<Vec<Plugin>, String> {
+    let plugins_dir = get_custom_plugins_dir();
+
+    let entries = match fs::read_dir(&plugins_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join(".git").exists())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+
+    let enabled: Vec<String> = OhMyZshBackend.list()?.into_iter().map(|plugin| plugin.name).collect();
+    let mut plugins = Vec::new();
+
+    let mut task = GitTask::new(&app, operation_id);
+    for name in names {
+        // One unreachable remote should not hide the others
+        if !task.stopped() {
+            let _ = task.run(plugins_dir.join(&name), &["fetch", "--progress"], None).await;
+        }
+        let is_enabled = enabled.contains(&name);
+        plugins.push(oh_my_zsh_plugin(&name, is_enabled));
+    }
+
+    task.finish(Ok(plugins))
+}
+
+/// Delete a custom plugin, disabling it first so oh-my-zsh does not warn about a missing plugin
+#[tauri::command]
+pub fn uninstall_plugin(name: String) -> Result<(), String> {
+    check_plugin_name(&name)?;
+
+    let dir = get_custom_plugins_dir().join(&name);
+    if !dir.exists() {
+        return Err(format!("Plugin '{}' is not installed in ~/.oh-my-zsh/custom/plugins", name));
+    }
+
+    if OhMyZshBackend.list()?.iter().any(|plugin| plugin.name == name) {
+        OhMyZshBackend.remove(&name)?;
+    }
+
+    fs::remove_dir_all(&dir)
+        .map_err(|e| format!("Failed to remove plugin '{}': {}", name, e))
+}
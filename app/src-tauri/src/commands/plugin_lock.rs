@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use super::git::{get_zsh_config_dir, run_git_command_in};
+use super::plugin_install::{check_plugin_name, get_custom_plugins_dir, read_plugin_repo};
+use super::plugin_managers::oh_my_zsh::OhMyZshBackend;
+use super::plugin_managers::PluginBackend;
+use super::progress::GitTask;
+
+pub(crate) const LOCKFILE_NAME: &str = "plugins.lock";
+
+/// Format version written to the lockfile; files with a newer version are refused
+const LOCKFILE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LockedPlugin {
+    pub name: String,
+    pub repository: String,
+    pub commit: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PluginLock {
+    pub version: u32,
+    pub plugins: Vec<LockedPlugin>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LockState {
+    /// Installed at the locked commit
+    Matches,
+    /// Installed at another commit
+    Differs,
+    NotInstalled,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LockCheck {
+    pub name: String,
+    pub state: LockState,
+    pub locked_commit: String,
+    pub installed_commit: Option<String>,
+}
+
+fn get_lockfile_path() -> PathBuf {
+    get_zsh_config_dir().join(LOCKFILE_NAME)
+}
+
+/// A full 40-character commit hash, as `write_plugin_lock` records
+fn is_full_commit(commit: &str) -> bool {
+    commit.len() == 40 && commit.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub(crate) fn read_lockfile() -> Result<Option<PluginLock>, String> {
+    let path = get_lockfile_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", LOCKFILE_NAME, e))?;
+    let lock: PluginLock = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", LOCKFILE_NAME, e))?;
+
+    if lock.version > LOCKFILE_VERSION {
+        return Err(format!(
+            "{} was written by a newer version of the app (format {})",
+            LOCKFILE_NAME, lock.version
+        ));
+    }
+
+    // The file is shared through git, so its entries decide what gets cloned and checked out
+    for plugin in &lock.plugins {
+        check_plugin_name(&plugin.name).map_err(|e| format!("{} in {}", e, LOCKFILE_NAME))?;
+        if !is_full_commit(&plugin.commit) {
+            return Err(format!(
+                "Invalid commit '{}' for plugin '{}' in {}",
+                plugin.commit, plugin.name, LOCKFILE_NAME
+            ));
+        }
+    }
+
+    Ok(Some(lock))
+}
+
+/// Compare a plugin's installed commit with the lockfile; None when it is not locked
+pub(crate) fn lock_state(lock: &PluginLock, name: &str, installed_commit: Option<&str>) -> Option<LockState> {
+    let locked = lock.plugins.iter().find(|plugin| plugin.name == name)?;
+
+    Some(match installed_commit {
+        None => LockState::NotInstalled,
+        Some(commit) if commit == locked.commit => LockState::Matches,
+        Some(_) => LockState::Differs,
+    })
+}
+
+fn installed_commit(name: &str) -> Option<String> {
+    read_plugin_repo(&get_custom_plugins_dir().join(name)).and_then(|repo| repo.commit)
+}
+
+/// Record the repository and commit of every enabled plugin installed with git
+#[tauri::command]
+pub fn write_plugin_lock() -> Result<PluginLock, String> {
+    let mut plugins: Vec<LockedPlugin> = OhMyZshBackend
+        .list()?
+        .iter()
+        .filter_map(|plugin| {
+            let repo = read_plugin_repo(&get_custom_plugins_dir().join(&plugin.name))?;
+            Some(LockedPlugin {
+                name: plugin.name.clone(),
+                repository: repo.remote_url?,
+                commit: repo.commit?,
+            })
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let lock = PluginLock {
+        version: LOCKFILE_VERSION,
+        plugins,
+    };
+
+    let content = serde_json::to_string_pretty(&lock)
+        .map_err(|e| format!("Failed to serialize {}: {}", LOCKFILE_NAME, e))?;
+    fs::write(get_lockfile_path(), content + "\n")
+        .map_err(|e| format!("Failed to write {}: {}", LOCKFILE_NAME, e))?;
+
+    Ok(lock)
+}
+
+#[tauri::command]
+pub fn check_plugin_lock() -> Result<Vec<LockCheck>, String> {
+    let Some(lock) = read_lockfile()? else {
+        return Ok(Vec::new());
+    };
+
+    Ok(lock
+        .plugins
+        .iter()
+        .map(|locked| {
+            let installed_commit = installed_commit(&locked.name);
+            LockCheck {
+                name: locked.name.clone(),
+                state: lock_state(&lock, &locked.name, installed_commit.as_deref()).unwrap_or(LockState::NotInstalled),
+                locked_commit: locked.commit.clone(),
+                installed_commit,
+            }
+        })
+        .collect())
+}
+
+/// Clone missing plugins and move installed ones to their locked commits.
+/// Plugins with local modifications are left alone and reported.
+#[tauri::command]
+pub async fn sync_plugin_lock(app: AppHandle, operation_id: Option<String>) -> Result<Vec<LockCheck>, String> {
+    let lock = read_lockfile()?.ok_or_else(|| format!("~/.zsh/{} does not exist", LOCKFILE_NAME))?;
+    let plugins_dir = get_custom_plugins_dir();
+    let mut errors = Vec::new();
+
+    fs::create_dir_all(&plugins_dir)
+        .map_err(|e| format!("Failed to create custom plugins directory: {}", e))?;
+
+    let mut task = GitTask::new(&app, operation_id);
+    for locked in &lock.plugins {
+        if task.stopped() {
+            break;
+        }
+
+        let dir = plugins_dir.join(&locked.name);
+        let current = installed_commit(&locked.name);
+        if current.as_deref() == Some(locked.commit.as_str()) {
+            continue;
+        }
+
+        if current.is_none() {
+            if dir.exists() {
+                errors.push(format!("{}: not a git clone, remove it to install the locked version", locked.name));
+                continue;
+            }
+            let args = ["clone", "--progress", "--", locked.repository.as_str(), locked.name.as_str()];
+            if let Err(e) = task.run(plugins_dir.clone(), &args, None).await {
+                errors.push(format!("{}: {}", locked.name, e));
+                continue;
+            }
+        } else {
+            let dirty = run_git_command_in(&dir, &["status", "--porcelain"])
+                .map(|status| !status.trim().is_empty())
+                .unwrap_or(true);
+            if dirty {
+                errors.push(format!("{}: has local modifications", locked.name));
+                continue;
+            }
+        }
+
+        // The locked commit may be newer than the clone
+        if run_git_command_in(&dir, &["cat-file", "-e", &format!("{}^{{commit}}", locked.commit)]).is_err() {
+            let _ = task.run(dir.clone(), &["fetch", "--progress"], None).await;
+        }
+
+        // Stay on the branch so `update_plugin` can still fast-forward later
+        if let Err(e) = run_git_command_in(&dir, &["reset", "--hard", "--quiet", &locked.commit]) {
+            errors.push(format!("{}: {}", locked.name, e));
+        }
+    }
+
+    let result = if errors.is_empty() {
+        check_plugin_lock()
+    } else {
+        Err(format!("Some plugins could not be synced:\n{}", errors.join("\n")))
+    };
+    task.finish(result)
+}
@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+
+use super::{env_dir, get_home_dir, plugin, repository_url, LineConfig, PluginBackend};
+use crate::commands::plugin::{Plugin, PluginManager};
+
+/// Bundles listed one per line in `${ZDOTDIR:-~}/.zsh_plugins.txt`, e.g. `zsh-users/zsh-completions kind:fpath`
+pub(crate) struct AntidoteBackend;
+
+fn get_bundle_file_path() -> PathBuf {
+    std::env::var_os("ZDOTDIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(get_home_dir)
+        .join(".zsh_plugins.txt")
+}
+
+/// The bundle of a line is its first word; annotations such as `kind:defer` follow it.
+/// Bundles are repositories or paths, which keeps ordinary comments from being read as bundles.
+fn parse_bundle(line: &str) -> Option<String> {
+    let bundle = line.split_whitespace().next()?;
+    ((bundle.contains('/') && !bundle.contains(':')) || bundle.contains("://")).then(|| bundle.to_string())
+}
+
+fn config() -> LineConfig<'static> {
+    LineConfig {
+        path: get_bundle_file_path(),
+        manager_name: "antidote",
+        parse: parse_bundle,
+        format: |name| name.to_string(),
+        before: None,
+    }
+}
+
+/// Antidote clones into `$ANTIDOTE_HOME`, either as `github.com/user/repo` (friendly names)
+/// or as the escaped URL `https-COLON--SLASH--SLASH-github.com-SLASH-user-SLASH-repo`
+fn is_installed(bundle: &str) -> bool {
+    if bundle.starts_with(['~', '$', '/']) {
+        let path = bundle.replacen('~', &get_home_dir().to_string_lossy(), 1);
+        return PathBuf::from(path).exists();
+    }
+
+    let Some(url) = repository_url(bundle) else {
+        return false;
+    };
+    let cache = env_dir("ANTIDOTE_HOME", ".cache/antidote");
+    let friendly = url.split_once("://").map(|(_, rest)| rest).unwrap_or(&url).trim_end_matches(".git");
+    let escaped = url.replace(':', "-COLON-").replace('/', "-SLASH-");
+
+    cache.join(friendly).exists() || cache.join(escaped).exists()
+}
+
+impl PluginBackend for AntidoteBackend {
+    fn manager(&self) -> PluginManager {
+        PluginManager::Antidote
+    }
+
+    fn detect(&self) -> bool {
+        get_bundle_file_path().exists()
+    }
+
+    fn list(&self) -> Result<Vec<Plugin>, String> {
+        Ok(config()
+            .entries()?
+            .iter()
+            .map(|entry| {
+                let installed = is_installed(&entry.name);
+                plugin(&entry.name, entry.enabled, installed, repository_url(&entry.name), PluginManager::Antidote)
+            })
+            .collect())
+    }
+
+    fn add(&self, name: &str) -> Result<(), String> {
+        config().add(name)
+    }
+
+    fn remove(&self, name: &str) -> Result<(), String> {
+        config().remove(name)
+    }
+
+    fn set_enabled(&self, name: &str, enabled: bool) -> Result<(), String> {
+        config().set_enabled(name, enabled)
+    }
+
+    /// Antidote loads repositories and paths, not bare oh-my-zsh names
+    fn accepts(&self, name: &str) -> bool {
+        parse_bundle(name).is_some()
+    }
+}
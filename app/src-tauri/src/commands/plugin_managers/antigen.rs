@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use super::{env_dir, get_zshrc_local_path, plugin, read_config, repository_url, LineConfig, PluginBackend};
+use crate::commands::plugin::{Plugin, PluginManager};
+
+/// `antigen bundle <user/repo|oh-my-zsh plugin>` lines in ~/.zshrc.local
+pub(crate) struct AntigenBackend;
+
+/// The bundle of an `antigen bundle` line, skipping options such as `--branch=main`
+fn parse_bundle(line: &str) -> Option<String> {
+    let mut words = line.split_whitespace();
+    if words.next()? != "antigen" || words.next()? != "bundle" {
+        return None;
+    }
+    words
+        .find(|word| !word.starts_with("--"))
+        .map(|word| word.trim_matches(|c| c == '"' || c == '\'').to_string())
+}
+
+fn config() -> LineConfig<'static> {
+    LineConfig {
+        path: get_zshrc_local_path(),
+        manager_name: "antigen",
+        parse: parse_bundle,
+        format: |name| format!("antigen bundle {}", name),
+        // Bundles declared after `antigen apply` are never loaded
+        before: Some("antigen apply"),
+    }
+}
+
+/// Antigen clones into `$ADOTDIR/bundles/user/repo`; bare names come from its oh-my-zsh clone
+fn is_installed(bundle: &str) -> bool {
+    let bundles = env_dir("ADOTDIR", ".antigen").join("bundles");
+    let path = if bundle.contains('/') {
+        PathBuf::from(bundle.trim_end_matches(".git"))
+    } else {
+        PathBuf::from("robbyrussell/oh-my-zsh/plugins").join(bundle)
+    };
+    bundles.join(path).exists()
+}
+
+impl PluginBackend for AntigenBackend {
+    fn manager(&self) -> PluginManager {
+        PluginManager::Antigen
+    }
+
+    fn detect(&self) -> bool {
+        read_config(&get_zshrc_local_path())
+            .ok()
+            .flatten()
+            .map(|content| content.contains("antigen.zsh") || content.contains("antigen bundle"))
+            .unwrap_or(false)
+    }
+
+    fn list(&self) -> Result<Vec<Plugin>, String> {
+        Ok(config()
+            .entries()?
+            .iter()
+            .map(|entry| {
+                let repository = if entry.name.contains('/') { repository_url(&entry.name) } else { None };
+                plugin(&entry.name, entry.enabled, is_installed(&entry.name), repository, PluginManager::Antigen)
+            })
+            .collect())
+    }
+
+    fn add(&self, name: &str) -> Result<(), String> {
+        config().add(name)
+    }
+
+    fn remove(&self, name: &str) -> Result<(), String> {
+        config().remove(name)
+    }
+
+    fn set_enabled(&self, name: &str, enabled: bool) -> Result<(), String> {
+        config().set_enabled(name, enabled)
+    }
+}
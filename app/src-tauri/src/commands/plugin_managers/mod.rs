@@ -0,0 +1,330 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::git::get_zsh_config_dir;
+use super::plugin::{Plugin, PluginManager};
+
+pub mod antidote;
+pub mod antigen;
+pub mod oh_my_zsh;
+pub mod sheldon;
+pub mod zinit;
+pub mod zplug;
+
+/// One plugin manager: where it declares plugins and how to edit that declaration
+pub(crate) trait PluginBackend {
+    fn manager(&self) -> PluginManager;
+
+    /// Whether this machine loads plugins with the manager
+    fn detect(&self) -> bool;
+
+    fn list(&self) -> Result<Vec<Plugin>, String>;
+
+    fn add(&self, name: &str) -> Result<(), String>;
+
+    fn remove(&self, name: &str) -> Result<(), String>;
+
+    /// Managers whose declarations can be commented out keep disabled plugins around
+    fn set_enabled(&self, name: &str, enabled: bool) -> Result<(), String> {
+        if enabled {
+            self.add(name)
+        } else {
+            self.remove(name)
+        }
+    }
+
+    /// Whether `name` is a plugin id this manager can load
+    fn accepts(&self, _name: &str) -> bool {
+        true
+    }
+}
+
+/// All backends, oh-my-zsh first so bare names like `git` go to its array when it is in use
+fn all_backends() -> Vec<Box<dyn PluginBackend>> {
+    vec![
+        Box::new(oh_my_zsh::OhMyZshBackend),
+        Box::new(zinit::ZinitBackend),
+        Box::new(antidote::AntidoteBackend),
+        Box::new(antigen::AntigenBackend),
+        Box::new(zplug::ZplugBackend),
+        Box::new(sheldon::SheldonBackend),
+    ]
+}
+
+/// The managers this machine uses; several can be combined (e.g. oh-my-zsh with zinit)
+pub(crate) fn detected_backends() -> Vec<Box<dyn PluginBackend>> {
+    all_backends().into_iter().filter(|backend| backend.detect()).collect()
+}
+
+/// Backend that should receive a new plugin, falling back to oh-my-zsh when none is detected
+pub(crate) fn backend_for_new_plugin(name: &str) -> Box<dyn PluginBackend> {
+    detected_backends()
+        .into_iter()
+        .find(|backend| backend.accepts(name))
+        .unwrap_or_else(|| Box::new(oh_my_zsh::OhMyZshBackend))
+}
+
+/// Backend that declares an existing plugin, enabled or not
+pub(crate) fn backend_declaring(name: &str) -> Result<Box<dyn PluginBackend>, String> {
+    for backend in detected_backends() {
+        if backend.list()?.iter().any(|plugin| plugin.name == name) {
+            return Ok(backend);
+        }
+    }
+    Err(format!("Plugin '{}' is not enabled", name))
+}
+
+pub(crate) fn get_home_dir() -> PathBuf {
+    dirs::home_dir().expect("Could not find home directory")
+}
+
+/// A directory from an environment variable, or `default` under the home directory
+pub(crate) fn env_dir(variable: &str, default: &str) -> PathBuf {
+    std::env::var_os(variable)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| get_home_dir().join(default))
+}
+
+pub(crate) fn get_zshrc_local_path() -> PathBuf {
+    get_home_dir().join(".zshrc.local")
+}
+
+/// $ZSH, or ~/.oh-my-zsh when it is not set
+pub(crate) fn get_oh_my_zsh_dir() -> PathBuf {
+    env_dir("ZSH", ".oh-my-zsh")
+}
+
+/// Config files the shell sources, in load order
+pub(crate) fn config_files() -> Vec<PathBuf> {
+    let zsh_dir = get_zsh_config_dir();
+    vec![
+        get_zshrc_local_path(),
+        zsh_dir.join("config.zsh"),
+        zsh_dir.join("aliases.zsh"),
+        zsh_dir.join("aliases.local.zsh"),
+        zsh_dir.join("functions.zsh"),
+        zsh_dir.join("functions.local.zsh"),
+    ]
+}
+
+pub(crate) fn relative_to_home(path: &Path) -> String {
+    path.strip_prefix(get_home_dir())
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string_lossy().to_string())
+}
+
+/// Read a config file, or None when it does not exist
+pub(crate) fn read_config(path: &Path) -> Result<Option<String>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    fs::read_to_string(path)
+        .map(Some)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+pub(crate) fn write_config(path: &Path, content: &str) -> Result<(), String> {
+    fs::write(path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+pub(crate) fn plugin(name: &str, enabled: bool, installed: bool, repository: Option<String>, manager: PluginManager) -> Plugin {
+    Plugin {
+        name: name.to_string(),
+        enabled,
+        installed,
+        description: None,
+        repository,
+        manager,
+        shared: false,
+        install_command: None,
+        version: None,
+        commit: None,
+        update_available: false,
+        lock_state: None,
+    }
+}
+
+/// GitHub page of a `user/repo` id, or the id itself when it is already a URL
+pub(crate) fn repository_url(id: &str) -> Option<String> {
+    if id.contains("://") || id.starts_with("git@") {
+        Some(id.to_string())
+    } else if id.split('/').count() == 2 && !id.starts_with(['~', '$', '/', '.']) {
+        Some(format!("https://github.com/{}", id))
+    } else {
+        None
+    }
+}
+
+/// A plugin declared on a single line, possibly commented out
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LineEntry {
+    pub name: String,
+    pub enabled: bool,
+    pub line: usize,
+}
+
+/// Prefix `set_lines_commented` puts on the lines of a disabled plugin
+pub(crate) const DISABLED_MARKER: &str = "# [disabled] ";
+
+/// Split a line into (enabled, code), for lines disabled by us or commented out by hand
+pub(crate) fn split_commented(line: &str) -> (bool, &str) {
+    let trimmed = line.trim();
+    if let Some(rest) = trimmed.strip_prefix(DISABLED_MARKER.trim_end()) {
+        return (false, rest.trim_start());
+    }
+    match trimmed.strip_prefix('#') {
+        Some(rest) => (false, rest.trim_start()),
+        None => (true, trimmed),
+    }
+}
+
+/// Find one-plugin-per-line declarations; `parse` returns the plugin id of a line of code
+pub(crate) fn parse_line_entries(content: &str, parse: impl Fn(&str) -> Option<String>) -> Vec<LineEntry> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let (enabled, code) = split_commented(line);
+            parse(code).map(|name| LineEntry {
+                name,
+                enabled,
+                line: index,
+            })
+        })
+        .collect()
+}
+
+fn join_lines(lines: &[String], trailing_newline: bool) -> String {
+    let mut content = lines.join("\n");
+    if trailing_newline {
+        content.push('\n');
+    }
+    content
+}
+
+/// Insert a line at `index`, or at the end of the file when it is past the last line
+pub(crate) fn insert_line(content: &str, index: usize, line: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+    lines.insert(index.min(lines.len()), line.to_string());
+    join_lines(&lines, true)
+}
+
+/// Where a new declaration goes: after the last existing one, otherwise before the first line
+/// matching `before` (such as `antigen apply`), otherwise at the end
+pub(crate) fn insertion_index(content: &str, last_entry: Option<usize>, before: Option<&str>) -> usize {
+    if let Some(last) = last_entry {
+        return last + 1;
+    }
+
+    before
+        .and_then(|marker| content.lines().position(|line| line.trim_start().starts_with(marker)))
+        .unwrap_or(usize::MAX)
+}
+
+/// Remove lines `start..=end`
+pub(crate) fn remove_lines(content: &str, start: usize, end: usize) -> String {
+    let lines: Vec<String> = content
+        .lines()
+        .enumerate()
+        .filter(|(index, _)| *index < start || *index > end)
+        .map(|(_, line)| line.to_string())
+        .collect();
+
+    join_lines(&lines, content.ends_with('\n'))
+}
+
+/// Disable lines `start..=end` by prefixing them with `DISABLED_MARKER`, or enable them by
+/// removing it, keeping their indentation. Comments in the block survive a round trip; only
+/// a block commented out by hand, without any marker, has one `#` removed per line.
+pub(crate) fn set_lines_commented(content: &str, start: usize, end: usize, commented: bool) -> String {
+    let marker = DISABLED_MARKER.trim_end();
+    let marked = content
+        .lines()
+        .skip(start)
+        .take(end + 1 - start)
+        .any(|line| line.trim_start().starts_with(marker));
+
+    let lines: Vec<String> = content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            if index < start || index > end || line.trim().is_empty() {
+                return line.to_string();
+            }
+
+            let indent_len = line.len() - line.trim_start().len();
+            let (indent, code) = line.split_at(indent_len);
+            if commented {
+                format!("{}{}{}", indent, DISABLED_MARKER, code)
+            } else if let Some(rest) = code.strip_prefix(marker) {
+                format!("{}{}", indent, rest.strip_prefix(' ').unwrap_or(rest))
+            } else if !marked {
+                let code = code.strip_prefix('#').unwrap_or(code);
+                format!("{}{}", indent, code.strip_prefix(' ').unwrap_or(code))
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+
+    join_lines(&lines, content.ends_with('\n'))
+}
+
+/// Add/remove/enable/disable for managers that declare one plugin per line in `path`
+pub(crate) struct LineConfig<'a> {
+    pub path: PathBuf,
+    pub manager_name: &'a str,
+    /// Plugin id of a line of code, if it declares one
+    pub parse: fn(&str) -> Option<String>,
+    /// Declaration line for a new plugin
+    pub format: fn(&str) -> String,
+    /// Line a first declaration must precede (e.g. `antigen apply`)
+    pub before: Option<&'a str>,
+}
+
+impl LineConfig<'_> {
+    pub fn entries(&self) -> Result<Vec<LineEntry>, String> {
+        Ok(read_config(&self.path)?
+            .map(|content| parse_line_entries(&content, self.parse))
+            .unwrap_or_default())
+    }
+
+    fn find(&self, content: &str, name: &str) -> Result<LineEntry, String> {
+        parse_line_entries(content, self.parse)
+            .into_iter()
+            .find(|entry| entry.name == name)
+            .ok_or_else(|| format!("Plugin '{}' is not loaded with {}", name, self.manager_name))
+    }
+
+    pub fn add(&self, name: &str) -> Result<(), String> {
+        let content = read_config(&self.path)?.unwrap_or_default();
+        let entries = parse_line_entries(&content, self.parse);
+
+        if let Some(entry) = entries.iter().find(|entry| entry.name == name) {
+            if entry.enabled {
+                return Err(format!("Plugin '{}' is already enabled", name));
+            }
+            return write_config(&self.path, &set_lines_commented(&content, entry.line, entry.line, false));
+        }
+
+        let index = insertion_index(&content, entries.iter().map(|entry| entry.line).max(), self.before);
+        write_config(&self.path, &insert_line(&content, index, &(self.format)(name)))
+    }
+
+    pub fn remove(&self, name: &str) -> Result<(), String> {
+        let content = read_config(&self.path)?.unwrap_or_default();
+        let entry = self.find(&content, name)?;
+        write_config(&self.path, &remove_lines(&content, entry.line, entry.line))
+    }
+
+    pub fn set_enabled(&self, name: &str, enabled: bool) -> Result<(), String> {
+        let content = read_config(&self.path)?.unwrap_or_default();
+        let entry = self.find(&content, name)?;
+        if entry.enabled == enabled {
+            return Ok(());
+        }
+        write_config(&self.path, &set_lines_commented(&content, entry.line, entry.line, !enabled))
+    }
+}
@@ -0,0 +1,337 @@
+use std::path::PathBuf;
+
+use super::{get_zshrc_local_path, read_config, write_config, PluginBackend};
+use crate::commands::git::get_zsh_config_dir;
+use crate::commands::plugin::{
+    find_plugins_array, oh_my_zsh_plugin, parse_plugins_from_file, ArrayEntry, Plugin, PluginManager,
+    PluginOrderWarning, PluginsArray,
+};
+
+/// The `plugins=(...)` array in ~/.zshrc.local, extended by the shared list in ~/.zsh/plugins.zsh
+pub(crate) struct OhMyZshBackend;
+
+/// Content of a new shared plugin list. Shared plugins load before the local ones, so plugins
+/// that must come last such as zsh-syntax-highlighting can stay last locally; `typeset -U`
+/// keeps the first occurrence of a plugin enabled in both lists.
+const SHARED_PLUGINS_TEMPLATE: &str = "# ~/.zsh/plugins.zsh
+# oh-my-zsh plugins enabled on every machine, loaded before the machine-specific ones
+# This file IS versioned
+
+typeset -U plugins
+plugins=(
+  $plugins
+)
+";
+
+/// Line of ~/.zshrc.local loading the shared list, between its own array and oh-my-zsh
+const SHARED_PLUGINS_SOURCE: &str = r#"[[ -f "$HOME/.zsh/plugins.zsh" ]] && source "$HOME/.zsh/plugins.zsh""#;
+
+/// Where a plugin has to be loaded relative to the others
+enum Placement {
+    /// After every other plugin except the listed ones
+    Last(&'static [&'static str]),
+    /// Before the listed plugins
+    Before(&'static [&'static str]),
+}
+
+struct OrderingRule {
+    plugin: &'static str,
+    placement: Placement,
+    reason: &'static str,
+}
+
+const SUBSTRING_SEARCH: &[&str] = &["history-substring-search", "zsh-history-substring-search"];
+
+const ORDERING_RULES: &[OrderingRule] = &[
+    OrderingRule {
+        plugin: "zsh-syntax-highlighting",
+        placement: Placement::Last(SUBSTRING_SEARCH),
+        reason: "it only highlights widgets defined before it",
+    },
+    OrderingRule {
+        plugin: "fast-syntax-highlighting",
+        placement: Placement::Last(SUBSTRING_SEARCH),
+        reason: "it only highlights widgets defined before it",
+    },
+    OrderingRule {
+        plugin: "fzf-tab",
+        placement: Placement::Before(&["zsh-autosuggestions", "zsh-syntax-highlighting", "fast-syntax-highlighting"]),
+        reason: "it has to bind tab before other plugins wrap widgets",
+    },
+];
+
+/// Why `first` has to be loaded before `second`, if it has to
+fn must_precede(first: &str, second: &str) -> Option<&'static str> {
+    ORDERING_RULES.iter().find_map(|rule| {
+        let applies = match rule.placement {
+            Placement::Last(except) => rule.plugin == second && first != second && !except.contains(&first),
+            Placement::Before(others) => rule.plugin == first && others.contains(&second),
+        };
+        applies.then_some(rule.reason)
+    })
+}
+
+/// Pairs of plugins loaded in an order that breaks one of the ordering rules
+pub(crate) fn order_warnings(plugins: &[String]) -> Vec<PluginOrderWarning> {
+    let mut warnings = Vec::new();
+
+    for (index, earlier) in plugins.iter().enumerate() {
+        for later in &plugins[index + 1..] {
+            if let Some(reason) = must_precede(later, earlier) {
+                warnings.push(PluginOrderWarning {
+                    plugin: earlier.clone(),
+                    message: format!("'{}' should be loaded after '{}': {}", earlier, later, reason),
+                });
+            }
+        }
+    }
+
+    warnings
+}
+
+fn line_start(content: &str, position: usize) -> usize {
+    content[..position].rfind('\n').map(|index| index + 1).unwrap_or(0)
+}
+
+/// End of the line containing `position`, or `limit` when that comes first
+fn line_end(content: &str, position: usize, limit: usize) -> usize {
+    content[position..limit].find('\n').map(|index| position + index).unwrap_or(limit)
+}
+
+/// Whether the entry is the first thing on its line, i.e. the array is written one per line
+fn starts_line(content: &str, entry: &ArrayEntry) -> bool {
+    content[line_start(content, entry.start)..entry.start].trim().is_empty()
+}
+
+fn indentation<'a>(content: &'a str, entry: &ArrayEntry) -> &'a str {
+    &content[line_start(content, entry.start)..entry.start]
+}
+
+/// Insert `name` before the entry at `index`, or after the last one, in the array's layout
+pub(crate) fn insert_entry(content: &str, array: &PluginsArray, index: usize, name: &str) -> String {
+    let (position, text) = if let Some(next) = array.entries.get(index) {
+        if starts_line(content, next) {
+            (line_start(content, next.start), format!("{}{}\n", indentation(content, next), name))
+        } else {
+            (next.start, format!("{} ", name))
+        }
+    } else if let Some(last) = array.entries.last() {
+        if starts_line(content, last) {
+            // After an inline comment such as `git # core`, on a line of its own
+            (line_end(content, last.end, array.close), format!("\n{}{}", indentation(content, last), name))
+        } else {
+            (last.end, format!(" {}", name))
+        }
+    } else if content[array.open..array.close].contains('\n') {
+        (array.open, format!("\n  {}", name))
+    } else {
+        (array.open, name.to_string())
+    };
+
+    format!("{}{}{}", &content[..position], text, &content[position..])
+}
+
+/// Remove one entry; a line holding only the entry and its comment is removed entirely
+pub(crate) fn remove_entry(content: &str, array: &PluginsArray, entry: &ArrayEntry) -> String {
+    let end_of_line = line_end(content, entry.end, array.close);
+    let rest = content[entry.end..end_of_line].trim();
+    let alone = starts_line(content, entry) && (rest.is_empty() || rest.starts_with('#'));
+
+    let (start, end) = if alone && end_of_line < array.close {
+        (line_start(content, entry.start), end_of_line + 1)
+    } else if alone {
+        // The closing parenthesis follows on the same line
+        (line_start(content, entry.start), end_of_line)
+    } else if content[entry.end..].starts_with([' ', '\t']) {
+        let spaces = content[entry.end..].len() - content[entry.end..].trim_start_matches([' ', '\t']).len();
+        (entry.start, entry.end + spaces)
+    } else {
+        let before = content[..entry.start].trim_end_matches([' ', '\t']).len();
+        (before.max(array.open), entry.end)
+    };
+
+    format!("{}{}", &content[..start], &content[end..])
+}
+
+/// Position for a new plugin: before the first one it has to precede, otherwise last
+fn insertion_index(array: &PluginsArray, name: &str) -> usize {
+    array
+        .entries
+        .iter()
+        .position(|entry| must_precede(name, &entry.name).is_some())
+        .unwrap_or(array.entries.len())
+}
+
+pub(crate) fn get_shared_plugins_path() -> PathBuf {
+    get_zsh_config_dir().join("plugins.zsh")
+}
+
+fn plugins_path(shared: bool) -> PathBuf {
+    if shared {
+        get_shared_plugins_path()
+    } else {
+        get_zshrc_local_path()
+    }
+}
+
+fn read_plugins_array(shared: bool) -> Result<(String, PluginsArray), String> {
+    let content = if shared {
+        read_config(&get_shared_plugins_path())?.unwrap_or_else(|| SHARED_PLUGINS_TEMPLATE.to_string())
+    } else {
+        read_config(&get_zshrc_local_path())?.ok_or_else(|| "~/.zshrc.local does not exist".to_string())?
+    };
+
+    let array = find_plugins_array(&content).ok_or_else(|| {
+        if shared {
+            "No plugins=(...) array in ~/.zsh/plugins.zsh".to_string()
+        } else {
+            "No plugins=(...) array in ~/.zshrc.local".to_string()
+        }
+    })?;
+    Ok((content, array))
+}
+
+/// Names in the shared list, or none when it does not exist
+fn shared_plugin_names() -> Result<Vec<String>, String> {
+    Ok(read_config(&get_shared_plugins_path())?
+        .map(|content| parse_plugins_from_file(&content))
+        .unwrap_or_default())
+}
+
+/// Local and shared names in the order oh-my-zsh loads them, each once as with `typeset -U`.
+/// The shared list is sourced after the local array and decides where the local names go.
+fn load_order(local: &[String]) -> Result<Vec<String>, String> {
+    let shared = read_config(&get_shared_plugins_path())?.and_then(|content| find_plugins_array(&content));
+
+    let names: Vec<String> = match shared {
+        None => local.to_vec(),
+        Some(array) => {
+            let shared: Vec<String> = array.entries.into_iter().map(|entry| entry.name).collect();
+            match array.existing_at {
+                Some(at) => shared[..at].iter().chain(local).chain(&shared[at..]).cloned().collect(),
+                None => shared,
+            }
+        }
+    };
+
+    let mut ordered: Vec<String> = Vec::new();
+    for name in names {
+        if !ordered.contains(&name) {
+            ordered.push(name);
+        }
+    }
+    Ok(ordered)
+}
+
+/// Make ~/.zshrc.local load the shared list right after its own array, before oh-my-zsh starts
+fn source_shared_plugins() -> Result<(), String> {
+    let path = get_zshrc_local_path();
+    let content = read_config(&path)?.unwrap_or_default();
+    if content.contains(".zsh/plugins.zsh") {
+        return Ok(());
+    }
+
+    // After the array's line, otherwise on a line of its own before oh-my-zsh, otherwise at the end
+    let (position, text) = if let Some(array) = find_plugins_array(&content) {
+        (line_end(&content, array.close, content.len()), format!("\n{}", SHARED_PLUGINS_SOURCE))
+    } else if let Some(index) = content.find("source $ZSH/oh-my-zsh.sh") {
+        (line_start(&content, index), format!("{}\n", SHARED_PLUGINS_SOURCE))
+    } else {
+        (content.trim_end().len(), format!("\n{}", SHARED_PLUGINS_SOURCE))
+    };
+
+    let new_content = format!("{}{}{}", &content[..position], text, &content[position..]);
+    write_config(&path, &new_content)
+}
+
+impl OhMyZshBackend {
+    /// Add a plugin to ~/.zshrc.local or to the shared list
+    pub(crate) fn add_to(&self, name: &str, shared: bool) -> Result<(), String> {
+        if !self.accepts(name) {
+            return Err(format!("Plugin '{}' is not an oh-my-zsh plugin", name));
+        }
+
+        let (content, array) = read_plugins_array(shared)?;
+        if array.entries.iter().any(|entry| entry.name == name) {
+            return Err(format!("Plugin '{}' is already enabled", name));
+        }
+
+        let index = insertion_index(&array, name);
+        write_config(&plugins_path(shared), &insert_entry(&content, &array, index, name))?;
+
+        if shared {
+            source_shared_plugins()?;
+        }
+        Ok(())
+    }
+
+    /// Remove every occurrence of a plugin from ~/.zshrc.local or from the shared list
+    pub(crate) fn remove_from(&self, name: &str, shared: bool) -> Result<(), String> {
+        let (mut content, array) = read_plugins_array(shared)?;
+
+        if !array.entries.iter().any(|entry| entry.name == name) {
+            let file = if shared { "~/.zsh/plugins.zsh" } else { "~/.zshrc.local" };
+            return Err(format!("Plugin '{}' is not enabled in {}", name, file));
+        }
+
+        // Duplicates are removed one at a time since offsets shift
+        while let Some(array) = find_plugins_array(&content) {
+            let Some(entry) = array.entries.iter().find(|entry| entry.name == name) else {
+                break;
+            };
+            content = remove_entry(&content, &array, entry);
+        }
+
+        write_config(&plugins_path(shared), &content)
+    }
+}
+
+impl PluginBackend for OhMyZshBackend {
+    fn manager(&self) -> PluginManager {
+        PluginManager::OhMyZsh
+    }
+
+    fn detect(&self) -> bool {
+        let local = read_config(&get_zshrc_local_path())
+            .ok()
+            .flatten()
+            .map(|content| find_plugins_array(&content).is_some())
+            .unwrap_or(false);
+
+        local || shared_plugin_names().map(|names| !names.is_empty()).unwrap_or(false)
+    }
+
+    /// Plugins in the order oh-my-zsh loads them; `shared` marks those only in the shared list
+    fn list(&self) -> Result<Vec<Plugin>, String> {
+        let local = read_config(&get_zshrc_local_path())?
+            .map(|content| parse_plugins_from_file(&content))
+            .unwrap_or_default();
+
+        Ok(load_order(&local)?
+            .iter()
+            .map(|name| {
+                let mut plugin = oh_my_zsh_plugin(name, true);
+                plugin.shared = !local.contains(name);
+                plugin
+            })
+            .collect())
+    }
+
+    fn add(&self, name: &str) -> Result<(), String> {
+        self.add_to(name, false)
+    }
+
+    /// Removes the local entry, or the shared one when the plugin is only enabled for everyone
+    fn remove(&self, name: &str) -> Result<(), String> {
+        let local = read_config(&get_zshrc_local_path())?
+            .map(|content| parse_plugins_from_file(&content))
+            .unwrap_or_default();
+
+        self.remove_from(name, !local.iter().any(|plugin| plugin == name))
+    }
+
+    /// oh-my-zsh plugins are plain directory names
+    fn accepts(&self, name: &str) -> bool {
+        !name.contains('/') && !name.contains("::")
+    }
+}
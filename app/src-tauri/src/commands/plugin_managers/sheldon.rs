@@ -0,0 +1,198 @@
+use std::path::PathBuf;
+
+use super::{
+    env_dir, get_home_dir, plugin, read_config, remove_lines, repository_url, set_lines_commented, split_commented,
+    write_config, PluginBackend,
+};
+use crate::commands::plugin::{Plugin, PluginManager};
+
+/// `[plugins.<name>]` tables in sheldon's plugins.toml
+pub(crate) struct SheldonBackend;
+
+/// A `[plugins.<name>]` table, commented out as a whole when disabled
+#[derive(Debug, Clone, PartialEq)]
+struct SheldonPlugin {
+    name: String,
+    enabled: bool,
+    /// `github = "user/repo"`, `git = "<url>"`, `remote = "<url>"` or `local = "<path>"`
+    source: Option<(String, String)>,
+    /// Header line and last non-blank line of the table, 0-based and inclusive
+    start: usize,
+    end: usize,
+}
+
+fn get_config_path() -> PathBuf {
+    if let Some(dir) = std::env::var_os("SHELDON_CONFIG_DIR") {
+        return PathBuf::from(dir).join("plugins.toml");
+    }
+    env_dir("XDG_CONFIG_HOME", ".config").join("sheldon/plugins.toml")
+}
+
+/// Plugin name of a `[plugins.<name>]` header; sub-tables such as `[plugins.<name>.hooks]` name the same plugin
+fn parse_header(code: &str) -> Option<String> {
+    let key = code.strip_prefix("[plugins.")?.strip_suffix(']')?;
+    let name = match key.strip_prefix('"') {
+        Some(quoted) => quoted.split_once('"')?.0,
+        None => key.split('.').next()?,
+    };
+    Some(name.trim().to_string()).filter(|name| !name.is_empty())
+}
+
+fn parse_plugins(content: &str) -> Vec<SheldonPlugin> {
+    let mut plugins: Vec<SheldonPlugin> = Vec::new();
+    let mut in_plugin = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let (enabled, code) = split_commented(line);
+
+        if code.starts_with('[') {
+            in_plugin = false;
+            if let Some(name) = parse_header(code) {
+                // A sub-table right after its plugin's table extends it
+                if let Some(current) = plugins.last_mut().filter(|p| p.name == name && p.enabled == enabled) {
+                    current.end = index;
+                    in_plugin = true;
+                    continue;
+                }
+                plugins.push(SheldonPlugin {
+                    name,
+                    enabled,
+                    source: None,
+                    start: index,
+                    end: index,
+                });
+                in_plugin = true;
+            }
+            continue;
+        }
+
+        let Some(current) = plugins.last_mut().filter(|_| in_plugin) else {
+            continue;
+        };
+        // Comments inside an enabled table, or plain lines after a disabled one, end nothing
+        // but do not belong to it either
+        if code.is_empty() || enabled != current.enabled {
+            continue;
+        }
+
+        current.end = index;
+        if let Some((key, value)) = code.split_once('=') {
+            let key = key.trim();
+            if ["github", "git", "remote", "local"].contains(&key) && current.source.is_none() {
+                current.source = Some((key.to_string(), value.trim().trim_matches('"').to_string()));
+            }
+        }
+    }
+
+    plugins
+}
+
+/// Sheldon clones into `$SHELDON_DATA_DIR/repos/<host>/<user>/<repo>`
+fn is_installed(plugin: &SheldonPlugin) -> bool {
+    let Some((kind, value)) = &plugin.source else {
+        return false;
+    };
+
+    if kind == "local" {
+        return PathBuf::from(value.replacen('~', &get_home_dir().to_string_lossy(), 1)).exists();
+    }
+
+    let repos = env_dir("SHELDON_DATA_DIR", ".local/share/sheldon").join("repos");
+    match repository_url(value) {
+        Some(url) => {
+            let path = url.split_once("://").map(|(_, rest)| rest).unwrap_or(&url).trim_end_matches(".git");
+            repos.join(path).exists()
+        }
+        None => false,
+    }
+}
+
+fn find_plugin(content: &str, name: &str) -> Result<SheldonPlugin, String> {
+    parse_plugins(content)
+        .into_iter()
+        .find(|plugin| plugin.name == name)
+        .ok_or_else(|| format!("Plugin '{}' is not loaded with sheldon", name))
+}
+
+fn read_plugins_toml() -> Result<String, String> {
+    read_config(&get_config_path())?.ok_or_else(|| "sheldon's plugins.toml does not exist".to_string())
+}
+
+impl PluginBackend for SheldonBackend {
+    fn manager(&self) -> PluginManager {
+        PluginManager::Sheldon
+    }
+
+    fn detect(&self) -> bool {
+        get_config_path().exists()
+    }
+
+    fn list(&self) -> Result<Vec<Plugin>, String> {
+        let content = read_config(&get_config_path())?.unwrap_or_default();
+
+        Ok(parse_plugins(&content)
+            .iter()
+            .map(|entry| {
+                let repository = entry
+                    .source
+                    .as_ref()
+                    .filter(|(kind, _)| kind != "local")
+                    .and_then(|(_, value)| repository_url(value));
+                plugin(&entry.name, entry.enabled, is_installed(entry), repository, PluginManager::Sheldon)
+            })
+            .collect())
+    }
+
+    /// Tables are named after the repository, e.g. `[plugins.zsh-autosuggestions]` for `github = "zsh-users/zsh-autosuggestions"`
+    fn add(&self, name: &str) -> Result<(), String> {
+        let content = read_plugins_toml()?;
+        let table = name.trim_end_matches(".git").rsplit('/').next().unwrap_or(name);
+
+        if let Some(existing) = parse_plugins(&content).into_iter().find(|plugin| plugin.name == table) {
+            if existing.enabled {
+                return Err(format!("Plugin '{}' is already enabled", table));
+            }
+            return write_config(&get_config_path(), &set_lines_commented(&content, existing.start, existing.end, false));
+        }
+
+        let source = if name.contains("://") || name.starts_with("git@") {
+            format!("git = \"{}\"", name)
+        } else if name.split('/').count() == 2 {
+            format!("github = \"{}\"", name)
+        } else {
+            return Err(format!("Sheldon plugins need a repository (user/repo or URL), got '{}'", name));
+        };
+
+        let mut new_content = content.trim_end().to_string();
+        if !new_content.is_empty() {
+            new_content.push_str("\n\n");
+        }
+        new_content.push_str(&format!("[plugins.{}]\n{}\n", table, source));
+
+        write_config(&get_config_path(), &new_content)
+    }
+
+    fn remove(&self, name: &str) -> Result<(), String> {
+        let content = read_plugins_toml()?;
+        let plugin = find_plugin(&content, name)?;
+
+        // Drop the blank line that separated the table from the previous one
+        let start = if plugin.start > 0 && content.lines().nth(plugin.start - 1).is_some_and(|line| line.trim().is_empty()) {
+            plugin.start - 1
+        } else {
+            plugin.start
+        };
+
+        write_config(&get_config_path(), &remove_lines(&content, start, plugin.end))
+    }
+
+    fn set_enabled(&self, name: &str, enabled: bool) -> Result<(), String> {
+        let content = read_plugins_toml()?;
+        let plugin = find_plugin(&content, name)?;
+        if plugin.enabled == enabled {
+            return Ok(());
+        }
+
+        write_config(&get_config_path(), &set_lines_commented(&content, plugin.start, plugin.end, !enabled))
+    }
+}
@@ -0,0 +1,214 @@
+use std::path::PathBuf;
+
+use super::{
+    get_home_dir, get_zshrc_local_path, insert_line, insertion_index, plugin, read_config, remove_lines,
+    repository_url, set_lines_commented, split_commented, write_config, PluginBackend,
+};
+use crate::commands::plugin::{Plugin, PluginManager};
+
+/// A plugin loaded with `zinit light|load <user/repo>` or `zinit snippet <url|OMZP::name>`,
+/// together with the `zinit ice ...` lines that apply to it
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ZinitEntry {
+    pub name: String,
+    /// `light`, `load` or `snippet`
+    pub command: String,
+    /// Modifiers of the preceding ice lines, as written
+    pub ice: Vec<String>,
+    /// Commented-out entries are disabled
+    pub enabled: bool,
+    /// First line of the entry (its first ice line) and the load line, both 0-based and inclusive
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Split a line into its zinit subcommand and arguments, noting whether it is commented out.
+/// `zi` is the short alias zinit installs.
+fn parse_zinit_line(line: &str) -> Option<(bool, &str, &str)> {
+    let (enabled, code) = split_commented(line);
+
+    let (command, rest) = code.split_once(char::is_whitespace)?;
+    if command != "zinit" && command != "zi" {
+        return None;
+    }
+
+    let rest = rest.trim();
+    let (subcommand, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    Some((enabled, subcommand, args.trim()))
+}
+
+pub(crate) fn parse_entries(content: &str) -> Vec<ZinitEntry> {
+    let mut entries = Vec::new();
+    // Ice lines waiting for the load line they apply to: (first line, enabled, modifiers)
+    let mut pending: Option<(usize, bool, Vec<String>)> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let Some((enabled, subcommand, args)) = parse_zinit_line(line) else {
+            // Ice applies to the next zinit command; anything but blank lines in between breaks the link
+            if !line.trim().is_empty() {
+                pending = None;
+            }
+            continue;
+        };
+
+        match subcommand {
+            "ice" => match &mut pending {
+                Some((_, pending_enabled, ice)) if *pending_enabled == enabled => ice.push(args.to_string()),
+                _ => pending = Some((index, enabled, vec![args.to_string()])),
+            },
+            "light" | "load" | "snippet" => {
+                // Skip options such as `-b` or `--command` before the plugin id
+                let Some(name) = args.split_whitespace().find(|arg| !arg.starts_with('-')) else {
+                    pending = None;
+                    continue;
+                };
+
+                let (start, ice) = match pending.take() {
+                    Some((start, pending_enabled, ice)) if pending_enabled == enabled => (start, ice),
+                    _ => (index, Vec::new()),
+                };
+
+                entries.push(ZinitEntry {
+                    name: name.trim_matches(|c| c == '"' || c == '\'').to_string(),
+                    command: subcommand.to_string(),
+                    ice,
+                    enabled,
+                    start,
+                    end: index,
+                });
+            }
+            _ => pending = None,
+        }
+    }
+
+    entries
+}
+
+/// Whether the file loads plugins with zinit or sources zinit itself
+pub(crate) fn uses_zinit(content: &str) -> bool {
+    !parse_entries(content).is_empty() || content.contains("zinit.zsh")
+}
+
+fn is_snippet(name: &str) -> bool {
+    name.contains("::") || name.contains("://")
+}
+
+/// Zinit's data directories: `$ZINIT_HOME/..`, the XDG default and the legacy ~/.zinit
+fn get_zinit_data_dirs() -> Vec<PathBuf> {
+    let home = get_home_dir();
+    let mut dirs = Vec::new();
+
+    if let Some(zinit_home) = std::env::var_os("ZINIT_HOME").map(PathBuf::from) {
+        if let Some(parent) = zinit_home.parent() {
+            dirs.push(parent.to_path_buf());
+        }
+    }
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".local/share"));
+    dirs.push(data_home.join("zinit"));
+    dirs.push(home.join(".zinit"));
+
+    dirs
+}
+
+/// Zinit clones `user/repo` to `plugins/user---repo` and stores snippets under `snippets/`
+/// with URL separators replaced (`OMZP::git` keeps its name)
+pub(crate) fn is_installed(entry: &ZinitEntry) -> bool {
+    let relative = if entry.command == "snippet" {
+        PathBuf::from("snippets").join(entry.name.replace("://", "--").replace('/', "--"))
+    } else {
+        PathBuf::from("plugins").join(entry.name.replace('/', "---"))
+    };
+
+    get_zinit_data_dirs().iter().any(|dir| dir.join(&relative).exists())
+}
+
+fn find_entry(content: &str, name: &str) -> Result<ZinitEntry, String> {
+    parse_entries(content)
+        .into_iter()
+        .find(|entry| entry.name == name)
+        .ok_or_else(|| format!("Plugin '{}' is not loaded with zinit", name))
+}
+
+/// Append a `zinit light` (or `zinit snippet`) line after the last zinit plugin
+pub(crate) fn add_entry(content: &str, name: &str) -> Result<String, String> {
+    let entries = parse_entries(content);
+    if entries.iter().any(|entry| entry.name == name) {
+        return Err(format!("Plugin '{}' is already loaded with zinit", name));
+    }
+
+    let command = if is_snippet(name) { "snippet" } else { "light" };
+    let line = format!("zinit {} {}", command, name);
+
+    let index = insertion_index(content, entries.iter().map(|entry| entry.end).max(), None);
+    Ok(insert_line(content, index, &line))
+}
+
+/// Remove the load line of a plugin and its ice lines
+pub(crate) fn remove_entry(content: &str, name: &str) -> Result<String, String> {
+    let entry = find_entry(content, name)?;
+    Ok(remove_lines(content, entry.start, entry.end))
+}
+
+/// Comment out or restore a plugin's lines; ice modifiers move with it unchanged
+pub(crate) fn set_entry_enabled(content: &str, name: &str, enabled: bool) -> Result<String, String> {
+    let entry = find_entry(content, name)?;
+    if entry.enabled == enabled {
+        return Ok(content.to_string());
+    }
+    Ok(set_lines_commented(content, entry.start, entry.end, !enabled))
+}
+
+/// Zinit declarations in ~/.zshrc.local
+pub(crate) struct ZinitBackend;
+
+impl ZinitBackend {
+    fn update(&self, edit: impl FnOnce(&str) -> Result<String, String>) -> Result<(), String> {
+        let path = get_zshrc_local_path();
+        let content = read_config(&path)?.ok_or("~/.zshrc.local does not exist")?;
+        write_config(&path, &edit(&content)?)
+    }
+}
+
+impl PluginBackend for ZinitBackend {
+    fn manager(&self) -> PluginManager {
+        PluginManager::Zinit
+    }
+
+    fn detect(&self) -> bool {
+        read_config(&get_zshrc_local_path())
+            .ok()
+            .flatten()
+            .map(|content| uses_zinit(&content))
+            .unwrap_or(false)
+    }
+
+    fn list(&self) -> Result<Vec<Plugin>, String> {
+        let content = read_config(&get_zshrc_local_path())?.unwrap_or_default();
+
+        Ok(parse_entries(&content)
+            .iter()
+            .map(|entry| {
+                let repository = if entry.command == "snippet" && !entry.name.contains("://") {
+                    None
+                } else {
+                    repository_url(&entry.name)
+                };
+                plugin(&entry.name, entry.enabled, is_installed(entry), repository, PluginManager::Zinit)
+            })
+            .collect())
+    }
+
+    fn add(&self, name: &str) -> Result<(), String> {
+        self.update(|content| add_entry(content, name))
+    }
+
+    fn remove(&self, name: &str) -> Result<(), String> {
+        self.update(|content| remove_entry(content, name))
+    }
+
+    fn set_enabled(&self, name: &str, enabled: bool) -> Result<(), String> {
+        self.update(|content| set_entry_enabled(content, name, enabled))
+    }
+}
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use super::{env_dir, get_zshrc_local_path, plugin, read_config, repository_url, LineConfig, PluginBackend};
+use crate::commands::plugin::{Plugin, PluginManager};
+
+/// `zplug "user/repo", tag:value, ...` lines in ~/.zshrc.local
+pub(crate) struct ZplugBackend;
+
+/// The quoted package name of a `zplug` line; `zplug load`, `zplug check` etc. are not declarations
+fn parse_package(line: &str) -> Option<String> {
+    let rest = line.strip_prefix("zplug")?.trim_start();
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let package = rest[1..].split(quote).next()?;
+    (!package.is_empty()).then(|| package.to_string())
+}
+
+fn config() -> LineConfig<'static> {
+    LineConfig {
+        path: get_zshrc_local_path(),
+        manager_name: "zplug",
+        parse: parse_package,
+        format: |name| format!("zplug \"{}\"", name),
+        // Packages declared after `zplug load` are not loaded
+        before: Some("zplug load"),
+    }
+}
+
+/// zplug clones into `$ZPLUG_REPOS/user/repo`; `plugins/x, from:oh-my-zsh` lives in its oh-my-zsh clone
+fn is_installed(package: &str) -> bool {
+    let repos = env_dir("ZPLUG_REPOS", ".zplug/repos");
+    repos.join(package).exists() || repos.join(PathBuf::from("robbyrussell/oh-my-zsh").join(package)).exists()
+}
+
+impl PluginBackend for ZplugBackend {
+    fn manager(&self) -> PluginManager {
+        PluginManager::Zplug
+    }
+
+    fn detect(&self) -> bool {
+        read_config(&get_zshrc_local_path())
+            .ok()
+            .flatten()
+            .map(|content| content.lines().any(|line| parse_package(line.trim()).is_some()))
+            .unwrap_or(false)
+    }
+
+    fn list(&self) -> Result<Vec<Plugin>, String> {
+        Ok(config()
+            .entries()?
+            .iter()
+            .map(|entry| {
+                let repository = if entry.name.starts_with("plugins/") { None } else { repository_url(&entry.name) };
+                plugin(&entry.name, entry.enabled, is_installed(&entry.name), repository, PluginManager::Zplug)
+            })
+            .collect())
+    }
+
+    fn add(&self, name: &str) -> Result<(), String> {
+        config().add(name)
+    }
+
+    fn remove(&self, name: &str) -> Result<(), String> {
+        config().remove(name)
+    }
+
+    fn set_enabled(&self, name: &str, enabled: bool) -> Result<(), String> {
+        config().set_enabled(name, enabled)
+    }
+}
@@ -0,0 +1,341 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Event carrying `GitProgress` payloads to the frontend
+pub const GIT_PROGRESS_EVENT: &str = "git-progress";
+
+/// Default time allowed for a network operation before it is killed
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+/// Output that means git or ssh needs credentials it cannot ask for
+const CREDENTIAL_PATTERNS: &[&str] = &[
+    "terminal prompts disabled",
+    "could not read username",
+    "could not read password",
+    "enter passphrase",
+    "permission denied (publickey",
+    "authentication failed",
+    "host key verification failed",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProgressKind {
+    Started,
+    Progress,
+    CredentialsRequired,
+    Done,
+    Failed,
+    Cancelled,
+    TimedOut,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitProgress {
+    pub operation_id: String,
+    pub kind: ProgressKind,
+    pub message: String,
+}
+
+fn operations() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    static OPERATIONS: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+    OPERATIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Use the id chosen by the frontend, or generate one it will learn from the first event
+fn operation_id(requested: Option<String>) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(1);
+    requested.unwrap_or_else(|| format!("git-{}", COUNTER.fetch_add(1, Ordering::Relaxed)))
+}
+
+pub(crate) fn emit_progress(app: &AppHandle, operation_id: &str, kind: ProgressKind, message: &str) {
+    let _ = app.emit(
+        GIT_PROGRESS_EVENT,
+        GitProgress {
+            operation_id: operation_id.to_string(),
+            kind,
+            message: message.to_string(),
+        },
+    );
+}
+
+fn needs_credentials(line: &str) -> bool {
+    let line = line.to_lowercase();
+    CREDENTIAL_PATTERNS.iter().any(|pattern| line.contains(pattern))
+}
+
+/// A failed run with the terminal event it calls for: Failed, Cancelled or TimedOut
+type RunError = (ProgressKind, String);
+
+/// Run git without blocking the UI, streaming stderr (where git reports progress) as events.
+/// Interactive prompts are disabled so a missing password or passphrase fails fast instead of hanging.
+pub(crate) async fn run_git_streaming(
+    app: &AppHandle,
+    operation_id: Option<String>,
+    dir: PathBuf,
+    args: &[&str],
+    timeout_secs: Option<u64>,
+) -> Result<String, String> {
+    let operation_id = self::operation_id(operation_id);
+    let result = run_git_step(app, &operation_id, dir, args, timeout_secs).await;
+
+    match &result {
+        Ok(_) => emit_progress(app, &operation_id, ProgressKind::Done, ""),
+        Err((kind, message)) => emit_progress(app, &operation_id, kind.clone(), message),
+    }
+    result.map_err(|(_, message)| message)
+}
+
+/// Run git streaming progress, leaving the terminal event to the caller
+async fn run_git_step(
+    app: &AppHandle,
+    operation_id: &str,
+    dir: PathBuf,
+    args: &[&str],
+    timeout_secs: Option<u64>,
+) -> Result<String, RunError> {
+    let app = app.clone();
+    let operation_id = operation_id.to_string();
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+
+    tauri::async_runtime::spawn_blocking(move || run_git_blocking(&app, &operation_id, &dir, &args, timeout))
+        .await
+        .map_err(|e| (ProgressKind::Failed, format!("Git task failed: {}", e)))?
+}
+
+/// Several git runs reported under one operation id, which ends with a single terminal event.
+/// Once a run is cancelled or times out, the remaining runs are not started.
+pub(crate) struct GitTask {
+    app: AppHandle,
+    operation_id: String,
+    stopped: Option<RunError>,
+}
+
+impl GitTask {
+    pub(crate) fn new(app: &AppHandle, operation_id: Option<String>) -> Self {
+        GitTask {
+            app: app.clone(),
+            operation_id: self::operation_id(operation_id),
+            stopped: None,
+        }
+    }
+
+    pub(crate) fn stopped(&self) -> bool {
+        self.stopped.is_some()
+    }
+
+    pub(crate) async fn run(&mut self, dir: PathBuf, args: &[&str], timeout_secs: Option<u64>) -> Result<String, String> {
+        if let Some((_, message)) = &self.stopped {
+            return Err(message.clone());
+        }
+
+        match run_git_step(&self.app, &self.operation_id, dir, args, timeout_secs).await {
+            Ok(output) => Ok(output),
+            Err((ProgressKind::Failed, message)) => Err(message),
+            Err((kind, message)) => {
+                self.stopped = Some((kind, message.clone()));
+                Err(message)
+            }
+        }
+    }
+
+    /// Send the terminal event for the whole command and pass its result through
+    pub(crate) fn finish<T>(self, result: Result<T, String>) -> Result<T, String> {
+        let (kind, message) = match (&self.stopped, &result) {
+            (Some((kind, message)), _) => (kind.clone(), message.clone()),
+            (None, Ok(_)) => (ProgressKind::Done, String::new()),
+            (None, Err(e)) => (ProgressKind::Failed, e.clone()),
+        };
+        emit_progress(&self.app, &self.operation_id, kind, &message);
+        result
+    }
+}
+
+fn run_git_blocking(
+    app: &AppHandle,
+    operation_id: &str,
+    dir: &Path,
+    args: &[String],
+    timeout: Duration,
+) -> Result<String, RunError> {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    operations()
+        .lock()
+        .map_err(|_| (ProgressKind::Failed, "Operation registry poisoned".to_string()))?
+        .insert(operation_id.to_string(), cancelled.clone());
+
+    let result = supervise(app, operation_id, dir, args, timeout, &cancelled);
+
+    if let Ok(mut running) = operations().lock() {
+        running.remove(operation_id);
+    }
+
+    result
+}
+
+/// Whether `core.sshCommand` is set, e.g. to use a specific key or ssh wrapper
+fn has_configured_ssh_command(dir: &Path) -> bool {
+    Command::new("git")
+        .args(["config", "--get", "core.sshCommand"])
+        .current_dir(dir)
+        .output()
+        .map(|output| output.status.success() && !output.stdout.trim_ascii().is_empty())
+        .unwrap_or(false)
+}
+
+fn supervise(
+    app: &AppHandle,
+    operation_id: &str,
+    dir: &Path,
+    args: &[String],
+    timeout: Duration,
+    cancelled: &AtomicBool,
+) -> Result<String, RunError> {
+    let failed = |message: String| (ProgressKind::Failed, message);
+    let mut command = Command::new("git");
+    command
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // Respect a user-provided ssh command, otherwise make ssh fail instead of prompting
+    if std::env::var_os("GIT_SSH_COMMAND").is_none() && !has_configured_ssh_command(dir) {
+        command.env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes");
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| failed(format!("Failed to execute git command: {}", e)))?;
+
+    emit_progress(app, operation_id, ProgressKind::Started, &format!("git {}", args.join(" ")));
+
+    let mut stdout = child.stdout.take().ok_or_else(|| failed("Failed to capture git output".to_string()))?;
+    let stdout_reader = thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stdout.read_to_string(&mut output);
+        output
+    });
+
+    let mut stderr = child.stderr.take().ok_or_else(|| failed("Failed to capture git output".to_string()))?;
+    let credentials_required = Arc::new(AtomicBool::new(false));
+    let stderr_reader = {
+        let app = app.clone();
+        let operation_id = operation_id.to_string();
+        let credentials_required = credentials_required.clone();
+
+        thread::spawn(move || {
+            let mut collected = String::new();
+            let mut line = Vec::new();
+            let mut buffer = [0u8; 1024];
+
+            // Progress meters redraw with '\r', so both '\r' and '\n' end a line
+            while let Ok(read) = stderr.read(&mut buffer) {
+                if read == 0 {
+                    break;
+                }
+                for &byte in &buffer[..read] {
+                    if byte != b'\r' && byte != b'\n' {
+                        line.push(byte);
+                        continue;
+                    }
+
+                    let text = String::from_utf8_lossy(&line).trim().to_string();
+                    line.clear();
+                    if text.is_empty() {
+                        continue;
+                    }
+
+                    let kind = if needs_credentials(&text) {
+                        credentials_required.store(true, Ordering::Relaxed);
+                        ProgressKind::CredentialsRequired
+                    } else {
+                        ProgressKind::Progress
+                    };
+                    emit_progress(&app, &operation_id, kind, &text);
+
+                    collected.push_str(&text);
+                    collected.push('\n');
+                }
+            }
+
+            collected.push_str(String::from_utf8_lossy(&line).trim());
+            collected
+        })
+    };
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| failed(format!("Failed to wait for git: {}", e)))? {
+            break Some(status);
+        }
+
+        if cancelled.load(Ordering::Relaxed) {
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+
+        if started.elapsed() > timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+
+        thread::sleep(Duration::from_millis(100));
+    };
+
+    // After a kill, helpers such as ssh may still hold the pipes open; don't wait for them
+    let status = match status {
+        Some(status) => status,
+        None if cancelled.load(Ordering::Relaxed) => {
+            return Err((ProgressKind::Cancelled, "Git operation cancelled".to_string()))
+        }
+        None => {
+            return Err((ProgressKind::TimedOut, format!("Git operation timed out after {}s", timeout.as_secs())))
+        }
+    };
+
+    let stdout = stdout_reader.join().unwrap_or_default();
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    match status {
+        status if status.success() => Ok(stdout),
+        _ if credentials_required.load(Ordering::Relaxed) => Err(failed(format!(
+            "Authentication required: git could not get credentials without a prompt. \
+             Configure a credential helper or ssh-agent and try again.\n{}",
+            stderr.trim()
+        ))),
+        _ => {
+            let message = if stderr.trim().is_empty() { stdout } else { stderr };
+            Err(failed(format!("Git command failed: {}", message)))
+        }
+    }
+}
+
+/// Stop a running git operation started with a progress id
+#[tauri::command]
+pub fn git_cancel(operation_id: String) -> Result<(), String> {
+    let running = operations()
+        .lock()
+        .map_err(|_| "Operation registry poisoned".to_string())?;
+
+    match running.get(&operation_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            Ok(())
+        }
+        None => Err(format!("No running operation '{}'", operation_id)),
+    }
+}
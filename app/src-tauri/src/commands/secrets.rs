@@ -0,0 +1,83 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use super::diff::managed_files;
+use super::git::get_zsh_config_dir;
+
+/// A line of a versioned file that looks like it contains a credential
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SecretFinding {
+    pub file: String,
+    pub line: usize,
+    pub rule: String,
+    /// The matched text with the secret part masked
+    pub excerpt: String,
+}
+
+/// Patterns for well-known token formats and for assignments to secret-looking variables.
+/// The value must be a literal: `$VAR` references to ~/.zshrc.secrets are fine.
+fn secret_rules() -> Vec<(&'static str, Regex)> {
+    [
+        ("private key", r"-----BEGIN [A-Z ]*PRIVATE KEY-----"),
+        ("AWS access key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+        ("GitHub token", r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,})"),
+        ("Slack token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}"),
+        ("OpenAI key", r"\bsk-[A-Za-z0-9_-]{20,}"),
+        (
+            "secret assignment",
+            r#"(?i)\b[A-Z0-9_]*(?:PASSWORD|PASSWD|SECRET|TOKEN|API_?KEY|ACCESS_?KEY)[A-Z0-9_]*=["']?[^\s"'$]{8,}"#,
+        ),
+    ]
+    .into_iter()
+    .map(|(name, pattern)| (name, Regex::new(pattern).unwrap()))
+    .collect()
+}
+
+/// Keep the start of a match and hide the rest
+fn mask(text: &str) -> String {
+    let visible: String = text.chars().take(6).collect();
+    format!("{}****", visible)
+}
+
+pub(crate) fn scan_content(file: &str, content: &str) -> Vec<SecretFinding> {
+    let rules = secret_rules();
+    let mut findings = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        // Report each line once, under the most specific rule
+        if let Some((rule, found)) = rules
+            .iter()
+            .find_map(|(rule, re)| re.find(line).map(|found| (rule, found)))
+        {
+            findings.push(SecretFinding {
+                file: file.to_string(),
+                line: index + 1,
+                rule: rule.to_string(),
+                excerpt: mask(found.as_str()),
+            });
+        }
+    }
+
+    findings
+}
+
+/// Scan the managed files in ~/.zsh, which are the ones that get committed and pushed
+pub(crate) fn find_secrets() -> Vec<SecretFinding> {
+    let zsh_dir = get_zsh_config_dir();
+
+    managed_files()
+        .iter()
+        .filter_map(|file| {
+            fs::read_to_string(zsh_dir.join(file))
+                .ok()
+                .map(|content| scan_content(file, &content))
+        })
+        .flatten()
+        .collect()
+}
+
+#[tauri::command]
+pub fn scan_secrets() -> Result<Vec<SecretFinding>, String> {
+    Ok(find_secrets())
+}
@@ -0,0 +1,301 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+use super::git::get_zsh_config_dir;
+use super::progress::run_git_streaming;
+
+/// Event carrying each `SetupStep` as it completes
+pub const SETUP_STEP_EVENT: &str = "setup-step";
+
+const BOOTSTRAP_ZSHRC: &str = include_str!("../../../../.zshrc");
+const ZSHRC_LOCAL_TEMPLATE: &str = include_str!("../../../../templates/.zshrc.local.template");
+const ALIASES_LOCAL_TEMPLATE: &str = include_str!("../../../../templates/aliases.local.zsh.template");
+const FUNCTIONS_LOCAL_TEMPLATE: &str = include_str!("../../../../templates/functions.local.zsh.template");
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SetupStatus {
+    Done,
+    Skipped,
+    Conflict,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetupStep {
+    pub step: String,
+    pub status: SetupStatus,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetupReport {
+    pub steps: Vec<SetupStep>,
+    pub backup_dir: Option<String>,
+}
+
+fn get_home_dir() -> PathBuf {
+    dirs::home_dir().expect("Could not find home directory")
+}
+
+fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Collects the setup steps and reports each one to the frontend as it happens
+struct SetupProgress<'a> {
+    app: &'a AppHandle,
+    steps: Vec<SetupStep>,
+}
+
+impl SetupProgress<'_> {
+    fn push(&mut self, step: &str, status: SetupStatus, message: String) {
+        let step = SetupStep {
+            step: step.to_string(),
+            status,
+            message,
+        };
+        let _ = self.app.emit(SETUP_STEP_EVENT, step.clone());
+        self.steps.push(step);
+    }
+}
+
+/// Expand "~/" so local repository paths can be given the way users type them
+pub(crate) fn expand_url(url: &str) -> String {
+    match url.strip_prefix("~/") {
+        Some(rest) => get_home_dir().join(rest).to_string_lossy().to_string(),
+        None => url.to_string(),
+    }
+}
+
+/// Files of a checkout, relative to its root, excluding the .git directory
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+
+    for entry in entries {
+        let path = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?.path();
+
+        if path.is_dir() {
+            if path.file_name().map(|n| n == ".git").unwrap_or(false) {
+                continue;
+            }
+            collect_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.push(relative.to_path_buf());
+        }
+    }
+
+    Ok(())
+}
+
+/// Copy a file to the backup directory, keeping its relative path
+fn backup_file(path: &Path, backup_dir: &Path, relative: &Path) -> Result<(), String> {
+    let target = backup_dir.join(relative);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    }
+
+    fs::copy(path, &target)
+        .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+    Ok(())
+}
+
+/// A file moved from the checkout into ~/.zsh and what it replaced
+struct MovedFile {
+    relative: PathBuf,
+    /// ~/.zsh already had the file
+    existed: bool,
+    /// The previous content differed and was backed up
+    backed_up: bool,
+}
+
+/// Move the cloned files into an existing ~/.zsh, backing up any file that would be overwritten.
+/// If a step fails, the files already moved go back to the checkout and the backed-up ones are restored.
+fn merge_checkout(
+    checkout: &Path,
+    zsh_dir: &Path,
+    backup_dir: &Path,
+    progress: &mut SetupProgress,
+) -> Result<(), String> {
+    let mut moved = Vec::new();
+    if let Err(e) = move_checkout(checkout, zsh_dir, backup_dir, progress, &mut moved) {
+        restore_moved(&moved, checkout, zsh_dir, backup_dir);
+        return Err(e);
+    }
+
+    fs::remove_dir_all(checkout)
+        .map_err(|e| format!("Failed to remove temporary checkout: {}", e))?;
+
+    Ok(())
+}
+
+fn move_checkout(
+    checkout: &Path,
+    zsh_dir: &Path,
+    backup_dir: &Path,
+    progress: &mut SetupProgress,
+    moved: &mut Vec<MovedFile>,
+) -> Result<(), String> {
+    let mut files = Vec::new();
+    collect_files(checkout, checkout, &mut files)?;
+
+    for relative in files {
+        let source = checkout.join(&relative);
+        let target = zsh_dir.join(&relative);
+
+        let existed = target.exists();
+        let backed_up = existed && fs::read(&source).ok() != fs::read(&target).ok();
+        if backed_up {
+            backup_file(&target, backup_dir, &relative)?;
+            progress.push(
+                "backup",
+                SetupStatus::Conflict,
+                format!("{} differs from the repository, backed up", relative.display()),
+            );
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::rename(&source, &target)
+            .map_err(|e| format!("Failed to move {}: {}", relative.display(), e))?;
+        moved.push(MovedFile { relative, existed, backed_up });
+    }
+
+    fs::rename(checkout.join(".git"), zsh_dir.join(".git"))
+        .map_err(|e| format!("Failed to move git directory: {}", e))
+}
+
+/// Undo `move_checkout` as far as possible, newest first
+fn restore_moved(moved: &[MovedFile], checkout: &Path, zsh_dir: &Path, backup_dir: &Path) {
+    for file in moved.iter().rev() {
+        let source = checkout.join(&file.relative);
+        let target = zsh_dir.join(&file.relative);
+
+        // An identical file was already there: leave it and put a copy back in the checkout
+        if file.existed && !file.backed_up {
+            let _ = fs::copy(&target, &source);
+            continue;
+        }
+
+        let _ = fs::rename(&target, &source);
+        if file.backed_up {
+            let _ = fs::copy(backup_dir.join(&file.relative), &target);
+        }
+    }
+}
+
+/// Create a local-only file, preferring the repository's own template when it ships one
+fn create_local_file(
+    path: &Path,
+    template_name: &str,
+    fallback: &str,
+    progress: &mut SetupProgress,
+) -> Result<(), String> {
+    let label = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+    if path.exists() {
+        progress.push("local-files", SetupStatus::Skipped, format!("{} already exists", label));
+        return Ok(());
+    }
+
+    let repo_template = get_zsh_config_dir().join("templates").join(template_name);
+    let content = fs::read_to_string(repo_template).unwrap_or_else(|_| fallback.to_string());
+
+    fs::write(path, content)
+        .map_err(|e| format!("Failed to create {}: {}", label, e))?;
+    progress.push("local-files", SetupStatus::Done, format!("Created {} from template", label));
+
+    Ok(())
+}
+
+/// Install the bootstrap ~/.zshrc unless the existing one already sources ~/.zsh
+fn wire_zshrc(backup_dir: &Path, progress: &mut SetupProgress) -> Result<(), String> {
+    let zshrc = get_home_dir().join(".zshrc");
+
+    if zshrc.exists() {
+        let content = fs::read_to_string(&zshrc)
+            .map_err(|e| format!("Failed to read .zshrc: {}", e))?;
+
+        if content.contains(".zsh/aliases.zsh") {
+            progress.push("zshrc", SetupStatus::Skipped, "~/.zshrc already loads ~/.zsh".to_string());
+            return Ok(());
+        }
+
+        backup_file(&zshrc, backup_dir, Path::new(".zshrc"))?;
+        progress.push("zshrc", SetupStatus::Conflict, "Existing ~/.zshrc backed up".to_string());
+    }
+
+    fs::write(&zshrc, BOOTSTRAP_ZSHRC)
+        .map_err(|e| format!("Failed to write .zshrc: {}", e))?;
+    progress.push("zshrc", SetupStatus::Done, "Installed bootstrap ~/.zshrc".to_string());
+
+    Ok(())
+}
+
+/// Set up a new machine from an existing dotfiles repository (remote URL, file:// URL or local path)
+#[tauri::command]
+pub async fn git_clone_setup(
+    app: AppHandle,
+    url: String,
+    branch: Option<String>,
+    operation_id: Option<String>,
+) -> Result<SetupReport, String> {
+    let url = expand_url(url.trim());
+    if url.is_empty() || url.starts_with('-') {
+        return Err(format!("Invalid repository URL '{}'", url));
+    }
+
+    let home = get_home_dir();
+    let zsh_dir = get_zsh_config_dir();
+    if zsh_dir.join(".git").exists() {
+        return Err("~/.zsh is already a git repository".to_string());
+    }
+
+    let stamp = timestamp();
+    let backup_dir = home.join(format!(".zsh-backup-{}", stamp));
+    let checkout = home.join(format!(".zsh-clone-{}", stamp));
+    let checkout_str = checkout.to_string_lossy().to_string();
+    let mut progress = SetupProgress { app: &app, steps: Vec::new() };
+
+    // Clone next to ~/.zsh first so a failed clone leaves nothing half-written
+    let mut args = vec!["clone", "--progress"];
+    if let Some(branch) = branch.as_deref() {
+        args.extend(["--branch", branch]);
+    }
+    args.extend(["--", url.as_str(), checkout_str.as_str()]);
+    run_git_streaming(&app, operation_id, home.clone(), &args, None)
+        .await
+        .map_err(|e| format!("Clone failed: {}", e))?;
+    progress.push("clone", SetupStatus::Done, format!("Cloned {}", url));
+
+    if zsh_dir.exists() {
+        merge_checkout(&checkout, &zsh_dir, &backup_dir, &mut progress)?;
+        progress.push("install", SetupStatus::Done, "Merged repository into ~/.zsh".to_string());
+    } else {
+        fs::rename(&checkout, &zsh_dir)
+            .map_err(|e| format!("Failed to move checkout to ~/.zsh: {}", e))?;
+        progress.push("install", SetupStatus::Done, "Repository installed in ~/.zsh".to_string());
+    }
+
+    create_local_file(&home.join(".zshrc.local"), ".zshrc.local.template", ZSHRC_LOCAL_TEMPLATE, &mut progress)?;
+    create_local_file(&zsh_dir.join("aliases.local.zsh"), "aliases.local.zsh.template", ALIASES_LOCAL_TEMPLATE, &mut progress)?;
+    create_local_file(&zsh_dir.join("functions.local.zsh"), "functions.local.zsh.template", FUNCTIONS_LOCAL_TEMPLATE, &mut progress)?;
+
+    wire_zshrc(&backup_dir, &mut progress)?;
+
+    Ok(SetupReport {
+        steps: progress.steps,
+        backup_dir: Some(backup_dir)
+            .filter(|dir| dir.exists())
+            .map(|dir| dir.to_string_lossy().to_string()),
+    })
+}
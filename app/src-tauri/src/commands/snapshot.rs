@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+use super::conflict::{list_conflicted_paths, operation_in_progress};
+use super::git::run_git_command;
+use super::gitignore::ensure_gitignore_rules;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RestoreResult {
+    pub output: String,
+    /// Hash of the stash holding the uncommitted edits saved before restoring, if any
+    pub stash: Option<String>,
+}
+
+fn resolve_commit(commit: &str) -> Result<String, String> {
+    run_git_command(&["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", commit)])
+        .map(|hash| hash.trim().to_string())
+        .map_err(|_| format!("Unknown commit '{}'", commit))
+}
+
+/// Only allow plain paths inside the repository
+fn check_path(path: &str) -> Result<(), String> {
+    if path.is_empty() || path.starts_with('/') || path.starts_with('-') || path.split('/').any(|part| part == "..") {
+        return Err(format!("Invalid path '{}'", path));
+    }
+    Ok(())
+}
+
+fn ensure_no_operation() -> Result<(), String> {
+    if let Some(operation) = operation_in_progress() {
+        let name = format!("{:?}", operation).to_lowercase();
+        return Err(format!("A {} is in progress; continue or abort it first", name));
+    }
+    Ok(())
+}
+
+/// Stash uncommitted edits (including untracked files) to `paths`, or the whole tree when empty.
+/// Returns the stash hash, or None when there was nothing to save.
+fn stash_changes(message: &str, paths: &[&str]) -> Result<Option<String>, String> {
+    // Without the rules, --include-untracked would sweep up machine-local files
+    ensure_gitignore_rules()?;
+
+    let mut status_args = vec!["status", "--porcelain", "--"];
+    status_args.extend(paths);
+    if run_git_command(&status_args)?.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let mut stash_args = vec!["stash", "push", "--include-untracked", "-m", message];
+    if !paths.is_empty() {
+        stash_args.push("--");
+        stash_args.extend(paths);
+    }
+    run_git_command(&stash_args)?;
+
+    let hash = run_git_command(&["rev-parse", "stash@{0}"])?;
+    Ok(Some(hash.trim().to_string()))
+}
+
+/// The `stash@{n}` reference of a stash saved by `stash_changes`
+fn stash_ref(hash: &str) -> Result<String, String> {
+    let hashes = run_git_command(&["stash", "list", "--format=%H"])?;
+    hashes
+        .lines()
+        .position(|line| line.trim() == hash)
+        .map(|index| format!("stash@{{{}}}", index))
+        .ok_or_else(|| format!("No stash '{}'", hash))
+}
+
+/// Content of a file as it was at `commit`
+#[tauri::command]
+pub fn git_show_file(commit: String, path: String) -> Result<String, String> {
+    check_path(&path)?;
+    let hash = resolve_commit(&commit)?;
+
+    run_git_command(&["show", &format!("{}:{}", hash, path)])
+        .map_err(|_| format!("'{}' does not exist at {}", path, commit))
+}
+
+/// Restore one file to its content at `commit`, stashing any uncommitted edits to it first
+#[tauri::command]
+pub fn git_restore_file(commit: String, path: String) -> Result<RestoreResult, String> {
+    check_path(&path)?;
+    ensure_no_operation()?;
+    let hash = resolve_commit(&commit)?;
+
+    let short = &hash[..7.min(hash.len())];
+    let stash = stash_changes(&format!("Auto-stash before restoring {} from {}", path, short), &[&path])?;
+    let output = run_git_command(&["checkout", &hash, "--", &path])?;
+
+    Ok(RestoreResult {
+        output: output.trim().to_string(),
+        stash,
+    })
+}
+
+/// Restore the whole config to its state at `commit` as new uncommitted changes
+#[tauri::command]
+pub fn git_restore_snapshot(commit: String) -> Result<RestoreResult, String> {
+    ensure_no_operation()?;
+    let hash = resolve_commit(&commit)?;
+
+    let short = &hash[..7.min(hash.len())];
+    let stash = stash_changes(&format!("Auto-stash before restoring snapshot {}", short), &[])?;
+    let output = run_git_command(&["restore", &format!("--source={}", hash), "--staged", "--worktree", "--", "."])?;
+
+    Ok(RestoreResult {
+        output: output.trim().to_string(),
+        stash,
+    })
+}
+
+/// Create a commit undoing `commit`, stashing uncommitted edits first
+#[tauri::command]
+pub fn git_revert_commit(commit: String) -> Result<RestoreResult, String> {
+    ensure_no_operation()?;
+    let hash = resolve_commit(&commit)?;
+
+    let short = &hash[..7.min(hash.len())];
+    let stash = stash_changes(&format!("Auto-stash before reverting {}", short), &[])?;
+
+    match run_git_command(&["revert", "--no-edit", &hash]) {
+        Ok(output) => Ok(RestoreResult {
+            output: output.trim().to_string(),
+            stash,
+        }),
+        Err(e) => {
+            let conflicted = list_conflicted_paths().unwrap_or_default();
+            if conflicted.is_empty() {
+                Err(e)
+            } else {
+                Err(format!(
+                    "Revert stopped with conflicts in: {}. Resolve them, then continue or abort the revert.",
+                    conflicted.join(", ")
+                ))
+            }
+        }
+    }
+}
+
+/// Reapply edits saved before a restore or revert, then drop the stash.
+/// On conflicts the stash is kept so nothing is lost.
+#[tauri::command]
+pub fn git_apply_stash(hash: String) -> Result<String, String> {
+    ensure_no_operation()?;
+    let stash = stash_ref(&hash)?;
+
+    let output = run_git_command(&["stash", "apply", &stash]).map_err(|e| {
+        let conflicted = list_conflicted_paths().unwrap_or_default();
+        if conflicted.is_empty() {
+            e
+        } else {
+            format!(
+                "The saved edits conflict in: {}. The stash was kept; resolve the files and drop it.",
+                conflicted.join(", ")
+            )
+        }
+    })?;
+    run_git_command(&["stash", "drop", "--quiet", &stash])?;
+
+    Ok(output.trim().to_string())
+}
+
+/// Discard edits saved before a restore or revert
+#[tauri::command]
+pub fn git_drop_stash(hash: String) -> Result<(), String> {
+    let stash = stash_ref(&hash)?;
+    run_git_command(&["stash", "drop", "--quiet", &stash])?;
+    Ok(())
+}
@@ -0,0 +1,420 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+use super::branch::current_upstream;
+use super::conflict::{list_conflicted_paths, operation_in_progress};
+use super::diff::{managed_files, suggest_commit_message, MANAGED_DIRS};
+use super::git::{get_zsh_config_dir, parse_porcelain_v2, run_git_command};
+use super::gitignore::{ensure_gitignore_rules, find_policy_violations};
+use super::progress::run_git_streaming;
+use super::secrets::find_secrets;
+
+/// Event carrying a `SyncEvent` for each action taken by the background sync
+pub const SYNC_EVENT: &str = "sync-event";
+
+/// How often the background sync checks the repository
+const TICK: Duration = Duration::from_secs(5);
+
+/// Longest wait between retries after repeated failures
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncPolicy {
+    /// Nothing happens until the user asks for a sync
+    #[default]
+    Manual,
+    /// Commit managed files after edits, never touch the network
+    CommitOnly,
+    /// Commit after edits, pull on a schedule and push when ahead
+    Full,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SyncSettings {
+    pub policy: SyncPolicy,
+    /// Time between scheduled pulls
+    pub interval_secs: u64,
+    /// Quiet time after the last edit before committing, so a burst of edits becomes one commit
+    pub commit_delay_secs: u64,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        SyncSettings {
+            policy: SyncPolicy::Manual,
+            interval_secs: 300,
+            commit_delay_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncAction {
+    Committed,
+    Pulled,
+    Pushed,
+    Paused,
+    Resumed,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SyncEvent {
+    pub action: SyncAction,
+    pub message: String,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SyncStatus {
+    pub settings: SyncSettings,
+    /// Why syncing is paused (conflicts, secrets), if it is
+    pub paused_reason: Option<String>,
+    pub last_event: Option<SyncEvent>,
+}
+
+struct SyncState {
+    settings: SyncSettings,
+    paused_reason: Option<String>,
+    last_event: Option<SyncEvent>,
+    last_pull: Option<Instant>,
+    failure: Option<Failure>,
+}
+
+/// A failed background cycle; retries back off until the managed files change again
+struct Failure {
+    count: u32,
+    retry_at: Instant,
+    last_edit: Option<SystemTime>,
+}
+
+/// Set while a sync cycle runs so the background loop and `sync_now` never overlap
+static SYNC_RUNNING: AtomicBool = AtomicBool::new(false);
+
+struct RunningGuard;
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        SYNC_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+fn state() -> &'static Mutex<SyncState> {
+    static STATE: OnceLock<Mutex<SyncState>> = OnceLock::new();
+    STATE.get_or_init(|| {
+        Mutex::new(SyncState {
+            settings: load_settings(),
+            paused_reason: None,
+            last_event: None,
+            last_pull: None,
+            failure: None,
+        })
+    })
+}
+
+fn with_state<T>(f: impl FnOnce(&mut SyncState) -> T) -> Result<T, String> {
+    let mut state = state().lock().map_err(|_| "Sync state poisoned".to_string())?;
+    Ok(f(&mut state))
+}
+
+fn get_settings_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| dirs::home_dir().expect("Could not find home directory").join(".config"))
+        .join("zsh-config-manager")
+        .join("sync.json")
+}
+
+fn load_settings() -> SyncSettings {
+    fs::read_to_string(get_settings_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn emit_sync(app: &AppHandle, action: SyncAction, message: String) {
+    let event = SyncEvent {
+        action,
+        message,
+        timestamp: timestamp(),
+    };
+    let _ = with_state(|state| state.last_event = Some(event.clone()));
+    let _ = app.emit(SYNC_EVENT, event);
+}
+
+/// Conditions under which nothing may be committed or pushed automatically
+fn pause_reason() -> Option<String> {
+    let conflicted = list_conflicted_paths().unwrap_or_default();
+    if !conflicted.is_empty() {
+        return Some(format!("Conflicts in {}; resolve them to resume syncing", conflicted.join(", ")));
+    }
+    if let Some(operation) = operation_in_progress() {
+        let name = format!("{:?}", operation).to_lowercase();
+        return Some(format!("A {} is in progress; continue or abort it to resume syncing", name));
+    }
+
+    let secrets = find_secrets();
+    if !secrets.is_empty() {
+        let locations: Vec<String> = secrets
+            .iter()
+            .map(|finding| format!("{}:{} ({})", finding.file, finding.line, finding.rule))
+            .collect();
+        return Some(format!(
+            "Possible secrets in {}; move them to ~/.zshrc.secrets to resume syncing",
+            locations.join(", ")
+        ));
+    }
+
+    let violations = find_policy_violations().unwrap_or_default();
+    if !violations.is_empty() {
+        return Some(format!(
+            "Local files are tracked: {}; untrack them to resume syncing",
+            violations.join(", ")
+        ));
+    }
+
+    None
+}
+
+/// Record the pause state, emitting an event only when it changes
+fn update_pause(app: &AppHandle) -> Result<bool, String> {
+    let reason = pause_reason();
+    let previous = with_state(|state| std::mem::replace(&mut state.paused_reason, reason.clone()))?;
+
+    match (&previous, &reason) {
+        (_, Some(reason)) if previous.as_ref() != Some(reason) => {
+            emit_sync(app, SyncAction::Paused, reason.clone());
+        }
+        (Some(_), None) => emit_sync(app, SyncAction::Resumed, "Sync resumed".to_string()),
+        _ => {}
+    }
+
+    Ok(reason.is_some())
+}
+
+/// Managed files with uncommitted changes
+fn changed_managed_files() -> Result<Vec<String>, String> {
+    let files = managed_files();
+    let mut args = vec!["status", "--porcelain=v2", "-z", "--"];
+    args.extend(files.iter().map(String::as_str));
+    let status = parse_porcelain_v2(&run_git_command(&args)?);
+
+    // Both sides of a rename must be committed
+    let renamed_from = status.renamed.into_iter().map(|rename| rename.from);
+    Ok(status.changed.into_iter().chain(renamed_from).chain(status.untracked).collect())
+}
+
+fn last_edit() -> Option<SystemTime> {
+    let zsh_dir = get_zsh_config_dir();
+    // A directory's own time changes when a file in it is deleted
+    managed_files()
+        .iter()
+        .map(String::as_str)
+        .chain(MANAGED_DIRS.iter().copied())
+        .filter_map(|file| fs::metadata(zsh_dir.join(file)).and_then(|m| m.modified()).ok())
+        .max()
+}
+
+/// Whether the managed files have not been touched for `delay`
+fn edits_settled(delay: Duration) -> bool {
+    match last_edit().and_then(|time| time.elapsed().ok()) {
+        Some(elapsed) => elapsed >= delay,
+        None => true,
+    }
+}
+
+/// Commit the changed managed files only, leaving anything else the user staged alone
+fn commit_managed_files(app: &AppHandle, files: &[String]) -> Result<(), String> {
+    let message = suggest_commit_message()?;
+
+    let mut paths: Vec<&str> = files.iter().map(String::as_str).collect();
+    if !ensure_gitignore_rules()?.is_empty() {
+        paths.push(".gitignore");
+    }
+
+    let mut add_args = vec!["add", "-A", "--"];
+    add_args.extend(&paths);
+    run_git_command(&add_args)?;
+
+    let mut commit_args = vec!["commit", "-m", message.as_str(), "--"];
+    commit_args.extend(&paths);
+    run_git_command(&commit_args)?;
+
+    emit_sync(app, SyncAction::Committed, message);
+    Ok(())
+}
+
+fn count_commits(range: &str) -> u32 {
+    run_git_command(&["rev-list", "--count", range])
+        .ok()
+        .and_then(|count| count.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Fetch, rebase onto the upstream when behind, then push when ahead
+async fn pull_and_push(app: &AppHandle) -> Result<(), String> {
+    let Some(upstream) = current_upstream() else {
+        return Ok(());
+    };
+    let remote = upstream.split('/').next().unwrap_or("origin").to_string();
+    let zsh_dir = get_zsh_config_dir();
+
+    run_git_streaming(app, None, zsh_dir.clone(), &["fetch", "--progress", &remote], None).await?;
+
+    let behind = count_commits(&format!("HEAD..{}", upstream));
+    if behind > 0 {
+        let args = ["pull", "--rebase", "--autostash", "--progress"];
+        if let Err(e) = run_git_streaming(app, None, zsh_dir.clone(), &args, None).await {
+            // A conflicting rebase pauses syncing until it is resolved
+            update_pause(app)?;
+            return Err(e);
+        }
+        emit_sync(app, SyncAction::Pulled, format!("Pulled {} commit(s) from {}", behind, upstream));
+    }
+
+    let ahead = count_commits(&format!("{}..HEAD", upstream));
+    if ahead > 0 {
+        run_git_streaming(app, None, zsh_dir, &["push", "--progress"], None).await?;
+        emit_sync(app, SyncAction::Pushed, format!("Pushed {} commit(s) to {}", ahead, upstream));
+    }
+
+    Ok(())
+}
+
+/// One pass of the sync engine. A manual sync ignores the policy and timers and does everything.
+async fn run_cycle(app: &AppHandle, manual: bool) -> Result<(), String> {
+    if SYNC_RUNNING.swap(true, Ordering::SeqCst) {
+        return if manual { Err("A sync is already running".to_string()) } else { Ok(()) };
+    }
+    let _guard = RunningGuard;
+
+    let settings = with_state(|state| state.settings.clone())?;
+    if !manual && settings.policy == SyncPolicy::Manual {
+        return Ok(());
+    }
+    if !get_zsh_config_dir().join(".git").exists() {
+        return if manual { Err("~/.zsh is not a git repository".to_string()) } else { Ok(()) };
+    }
+
+    if update_pause(app)? {
+        let reason = with_state(|state| state.paused_reason.clone())?.unwrap_or_default();
+        return if manual { Err(reason) } else { Ok(()) };
+    }
+
+    let changed = changed_managed_files()?;
+    if !changed.is_empty() && (manual || edits_settled(Duration::from_secs(settings.commit_delay_secs))) {
+        commit_managed_files(app, &changed)?;
+    }
+
+    let pull_due = with_state(|state| {
+        state
+            .last_pull
+            .map(|last| last.elapsed() >= Duration::from_secs(settings.interval_secs))
+            .unwrap_or(true)
+    })?;
+    if manual || (settings.policy == SyncPolicy::Full && pull_due) {
+        with_state(|state| state.last_pull = Some(Instant::now()))?;
+        pull_and_push(app).await?;
+    }
+
+    Ok(())
+}
+
+/// Whether the background loop should wait: a failure is retried after its backoff,
+/// or straight away once the managed files are edited again
+fn backing_off() -> bool {
+    let edit = last_edit();
+    with_state(|state| match &state.failure {
+        Some(failure) if failure.last_edit != edit => {
+            state.failure = None;
+            false
+        }
+        Some(failure) => Instant::now() < failure.retry_at,
+        None => false,
+    })
+    .unwrap_or(false)
+}
+
+/// Record a failed background cycle, doubling the wait after each consecutive one
+fn record_failure() {
+    let edit = last_edit();
+    let _ = with_state(|state| {
+        let count = state.failure.as_ref().map(|failure| failure.count + 1).unwrap_or(1);
+        let backoff = TICK.saturating_mul(2u32.saturating_pow(count)).min(MAX_BACKOFF);
+        state.failure = Some(Failure {
+            count,
+            retry_at: Instant::now() + backoff,
+            last_edit: edit,
+        });
+    });
+}
+
+/// Start the background sync loop; it does nothing while the policy is manual
+pub fn start_sync_engine(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(TICK);
+        if backing_off() {
+            continue;
+        }
+        match tauri::async_runtime::block_on(run_cycle(&app, false)) {
+            Ok(()) => {
+                let _ = with_state(|state| state.failure = None);
+            }
+            Err(e) => {
+                record_failure();
+                emit_sync(&app, SyncAction::Failed, e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_sync_status() -> Result<SyncStatus, String> {
+    with_state(|state| SyncStatus {
+        settings: state.settings.clone(),
+        paused_reason: state.paused_reason.clone(),
+        last_event: state.last_event.clone(),
+    })
+}
+
+#[tauri::command]
+pub fn set_sync_settings(settings: SyncSettings) -> Result<(), String> {
+    let path = get_settings_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize sync settings: {}", e))?;
+    fs::write(&path, content)
+        .map_err(|e| format!("Failed to write sync settings: {}", e))?;
+
+    with_state(|state| state.settings = settings)
+}
+
+/// Commit, pull and push now, whatever the policy
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> Result<(), String> {
+    let result = run_cycle(&app, true).await;
+    match &result {
+        Ok(()) => with_state(|state| state.failure = None)?,
+        Err(e) => emit_sync(&app, SyncAction::Failed, e.clone()),
+    }
+    result
+}
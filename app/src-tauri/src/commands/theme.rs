@@ -0,0 +1,477 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use regex::Regex;
+
+use super::git::get_zsh_config_dir;
+use super::hooks::check_zsh_syntax;
+use super::plugin_managers::{get_home_dir, get_oh_my_zsh_dir, get_zshrc_local_path, read_config, relative_to_home, write_config};
+
+/// How long the sandboxed zsh may take to render a prompt
+const PREVIEW_TIMEOUT: Duration = Duration::from_secs(10);
+
+const STARSHIP_INIT: &str = r#"eval "$(starship init zsh)""#;
+
+/// Run the prompt hooks, then print the expanded left and right prompts separated by a NUL
+const PROMPT_RENDER: &str = r#"for f in $precmd_functions; do $f; done 2>/dev/null
+print -rnP -- "$PROMPT"
+print -rn -- $'\0'
+print -rnP -- "$RPROMPT"
+"#;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PromptSystem {
+    OhMyZsh,
+    Powerlevel10k,
+    Starship,
+    /// A PROMPT or PS1 set by hand
+    Custom,
+    None,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Theme {
+    /// Value for ZSH_THEME, e.g. "robbyrussell" or "powerlevel10k/powerlevel10k", or "starship"
+    pub name: String,
+    pub system: PromptSystem,
+    /// Ships with oh-my-zsh rather than living in ~/.oh-my-zsh/custom/themes
+    pub builtin: bool,
+    pub active: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptStatus {
+    pub system: PromptSystem,
+    /// ZSH_THEME, or "starship"
+    pub theme: Option<String>,
+    /// Configuration of the prompt, e.g. ~/.p10k.zsh or ~/.config/starship.toml, when it exists
+    pub config_file: Option<String>,
+    /// The file that selects the prompt, relative to the home directory
+    pub defined_in: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptPreview {
+    pub theme: Option<String>,
+    /// Left and right prompts with their ANSI colors
+    pub prompt: String,
+    pub right_prompt: String,
+}
+
+fn get_p10k_config_path() -> PathBuf {
+    get_home_dir().join(".p10k.zsh")
+}
+
+fn get_starship_config_path() -> PathBuf {
+    std::env::var_os("STARSHIP_CONFIG")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| get_home_dir().join(".config/starship.toml"))
+}
+
+/// Files that can select the prompt, in the order ~/.zshrc loads them
+fn prompt_files() -> Vec<PathBuf> {
+    vec![get_zshrc_local_path(), get_zsh_config_dir().join("config.zsh"), get_home_dir().join(".zshrc")]
+}
+
+fn is_code(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+fn zsh_theme_regex() -> Regex {
+    Regex::new(r#"^\s*(?:export\s+)?ZSH_THEME=(?:"([^"]*)"|'([^']*)'|([^\s#;]*))"#).unwrap()
+}
+
+/// Value of the last uncommented `ZSH_THEME=` assignment
+fn parse_zsh_theme(content: &str) -> Option<String> {
+    let re = zsh_theme_regex();
+    content
+        .lines()
+        .filter_map(|line| re.captures(line))
+        .next_back()
+        .and_then(|captures| captures.get(1).or_else(|| captures.get(2)).or_else(|| captures.get(3)))
+        .map(|value| value.as_str().to_string())
+}
+
+fn uses_starship(content: &str) -> bool {
+    content.lines().any(|line| is_code(line) && line.contains("starship init zsh"))
+}
+
+fn sets_prompt(content: &str) -> bool {
+    let re = Regex::new(r"^\s*(?:export\s+)?(?:PROMPT|PS1)=").unwrap();
+    content.lines().any(|line| re.is_match(line))
+}
+
+fn sources_p10k(content: &str) -> bool {
+    content.lines().any(|line| is_code(line) && line.contains("powerlevel10k.zsh-theme"))
+}
+
+fn existing(path: PathBuf) -> Option<String> {
+    path.exists().then(|| relative_to_home(&path))
+}
+
+/// Starship wins over any theme since its init replaces the prompt last,
+/// then powerlevel10k, then an oh-my-zsh theme, then a hand-written PROMPT
+fn detect_prompt() -> Result<PromptStatus, String> {
+    let files: Vec<(PathBuf, String)> = prompt_files()
+        .into_iter()
+        .filter_map(|path| read_config(&path).ok().flatten().map(|content| (path, content)))
+        .collect();
+
+    if let Some((path, _)) = files.iter().find(|(_, content)| uses_starship(content)) {
+        return Ok(PromptStatus {
+            system: PromptSystem::Starship,
+            theme: Some("starship".to_string()),
+            config_file: existing(get_starship_config_path()),
+            defined_in: Some(relative_to_home(path)),
+        });
+    }
+
+    let theme = files
+        .iter()
+        .find_map(|(path, content)| parse_zsh_theme(content).filter(|t| !t.is_empty()).map(|t| (path, t)));
+
+    if let Some((path, theme)) = &theme {
+        let system = if theme.starts_with("powerlevel10k") {
+            PromptSystem::Powerlevel10k
+        } else {
+            PromptSystem::OhMyZsh
+        };
+        let config_file = match system {
+            PromptSystem::Powerlevel10k => existing(get_p10k_config_path()),
+            _ => None,
+        };
+        return Ok(PromptStatus {
+            system,
+            theme: Some(theme.clone()),
+            config_file,
+            defined_in: Some(relative_to_home(path)),
+        });
+    }
+
+    if let Some((path, _)) = files.iter().find(|(_, content)| sources_p10k(content)) {
+        return Ok(PromptStatus {
+            system: PromptSystem::Powerlevel10k,
+            theme: None,
+            config_file: existing(get_p10k_config_path()),
+            defined_in: Some(relative_to_home(path)),
+        });
+    }
+
+    if let Some((path, _)) = files.iter().find(|(_, content)| sets_prompt(content)) {
+        return Ok(PromptStatus {
+            system: PromptSystem::Custom,
+            theme: None,
+            config_file: None,
+            defined_in: Some(relative_to_home(path)),
+        });
+    }
+
+    Ok(PromptStatus {
+        system: PromptSystem::None,
+        theme: None,
+        config_file: None,
+        defined_in: None,
+    })
+}
+
+/// `*.zsh-theme` files directly in `dir`, and `<name>/<name>.zsh-theme` as installed by git clones
+fn theme_names(dir: &Path) -> Vec<String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(theme) = name.strip_suffix(".zsh-theme") {
+                Some(theme.to_string())
+            } else if path.join(format!("{}.zsh-theme", name)).exists() {
+                Some(format!("{}/{}", name, name))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn is_starship_installed() -> bool {
+    Command::new("starship")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+fn available_themes(active: Option<&str>) -> Vec<Theme> {
+    let zsh = get_oh_my_zsh_dir();
+    let mut themes: Vec<Theme> = Vec::new();
+
+    for (dir, builtin) in [(zsh.join("custom/themes"), false), (zsh.join("themes"), true)] {
+        for name in theme_names(&dir) {
+            // Custom themes shadow built-in ones of the same name
+            if themes.iter().any(|theme| theme.name == name) {
+                continue;
+            }
+            let system = if name.starts_with("powerlevel10k") {
+                PromptSystem::Powerlevel10k
+            } else {
+                PromptSystem::OhMyZsh
+            };
+            themes.push(Theme {
+                active: active == Some(name.as_str()),
+                name,
+                system,
+                builtin,
+            });
+        }
+    }
+
+    if is_starship_installed() {
+        themes.push(Theme {
+            name: "starship".to_string(),
+            system: PromptSystem::Starship,
+            builtin: false,
+            active: active == Some("starship"),
+        });
+    }
+
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+    themes
+}
+
+/// Set ZSH_THEME in place, or before oh-my-zsh is loaded, or at the end
+fn set_zsh_theme(content: &str, theme: &str) -> String {
+    let re = zsh_theme_regex();
+    let assignment = format!("ZSH_THEME=\"{}\"", theme);
+
+    let mut lines: Vec<String> = content.lines().map(|line| line.to_string()).collect();
+
+    // The last assignment is the one that takes effect; its trailing comment is kept
+    if let Some(index) = lines.iter().rposition(|line| re.is_match(line)) {
+        lines[index] = re.replace(&lines[index], regex::NoExpand(&assignment)).to_string();
+        return join_lines(&lines, content.ends_with('\n'));
+    }
+
+    let index = lines
+        .iter()
+        .position(|line| is_code(line) && line.contains("oh-my-zsh.sh"))
+        .unwrap_or(lines.len());
+    lines.insert(index, assignment);
+    join_lines(&lines, true)
+}
+
+/// Comment out or restore `eval "$(starship init zsh)"`, adding it at the end when enabling
+fn set_starship(content: &str, enabled: bool) -> String {
+    let mut found = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            if !line.contains("starship init zsh") {
+                return line.to_string();
+            }
+            found = true;
+            let code = line.trim_start().trim_start_matches('#').trim_start();
+            if enabled {
+                code.to_string()
+            } else if is_code(line) {
+                format!("# {}", line.trim_start())
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+
+    if enabled && !found {
+        lines.push(STARSHIP_INIT.to_string());
+    }
+    join_lines(&lines, content.ends_with('\n') || (enabled && !found))
+}
+
+fn join_lines(lines: &[String], trailing_newline: bool) -> String {
+    let mut content = lines.join("\n");
+    if trailing_newline {
+        content.push('\n');
+    }
+    content
+}
+
+/// Active prompt system and theme
+#[tauri::command]
+pub fn get_prompt_status() -> Result<PromptStatus, String> {
+    detect_prompt()
+}
+
+/// oh-my-zsh themes installed on this machine, plus starship when its binary is available
+#[tauri::command]
+pub fn list_themes() -> Result<Vec<Theme>, String> {
+    let status = detect_prompt()?;
+    Ok(available_themes(status.theme.as_deref()))
+}
+
+/// Switch to an installed theme (or "starship") by editing ~/.zshrc.local.
+/// Only ZSH_THEME and the starship init line change, and the result must pass `zsh -n`.
+/// Returns the previous status so the switch can be undone.
+#[tauri::command]
+pub fn set_theme(name: String) -> Result<PromptStatus, String> {
+    let previous = detect_prompt()?;
+    let themes = available_themes(previous.theme.as_deref());
+    if !themes.iter().any(|theme| theme.name == name) {
+        return Err(format!("Theme '{}' is not installed", name));
+    }
+
+    // Starship initialized outside ~/.zshrc.local would still override the new theme
+    if name != "starship" {
+        let other = prompt_files().into_iter().skip(1).find(|file| {
+            read_config(file).ok().flatten().map(|content| uses_starship(&content)).unwrap_or(false)
+        });
+        if let Some(file) = other {
+            return Err(format!("{} initializes starship, which overrides ZSH_THEME", relative_to_home(&file)));
+        }
+    }
+
+    let path = get_zshrc_local_path();
+    let content = read_config(&path)?.ok_or_else(|| "~/.zshrc.local does not exist".to_string())?;
+
+    let new_content = if name == "starship" {
+        // An empty ZSH_THEME keeps oh-my-zsh from drawing its own prompt first
+        let content = if parse_zsh_theme(&content).is_some() { set_zsh_theme(&content, "") } else { content };
+        set_starship(&content, true)
+    } else {
+        set_starship(&set_zsh_theme(&content, &name), false)
+    };
+
+    if let Some(error) = check_zsh_syntax(&new_content) {
+        return Err(format!("Theme change would break ~/.zshrc.local: {}", error));
+    }
+
+    write_config(&path, &new_content)?;
+    Ok(previous)
+}
+
+fn preview_script(theme: &str) -> String {
+    let zsh = get_oh_my_zsh_dir();
+
+    if theme == "starship" {
+        return format!("{}\n{}", STARSHIP_INIT, PROMPT_RENDER);
+    }
+
+    let p10k = if theme.starts_with("powerlevel10k") && get_p10k_config_path().exists() {
+        format!("source {}\n", shell_quote(&get_p10k_config_path().to_string_lossy()))
+    } else {
+        String::new()
+    };
+
+    format!(
+        "export ZSH={}\n\
+         ZSH_THEME={}\n\
+         plugins=()\n\
+         DISABLE_AUTO_UPDATE=true\n\
+         ZSH_DISABLE_COMPFIX=true\n\
+         POWERLEVEL9K_DISABLE_GITSTATUS=true\n\
+         POWERLEVEL9K_INSTANT_PROMPT=off\n\
+         source $ZSH/oh-my-zsh.sh\n\
+         {}{}",
+        shell_quote(&zsh.to_string_lossy()),
+        shell_quote(theme),
+        p10k,
+        PROMPT_RENDER
+    )
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Run `script` in `zsh -f` with a minimal environment, killing it after PREVIEW_TIMEOUT
+fn run_sandboxed_zsh(script: &str) -> Result<String, String> {
+    let home = get_home_dir();
+    let mut command = Command::new("zsh");
+    command
+        .args(["-f", "-c", script])
+        .current_dir(&home)
+        .env_clear()
+        .env("HOME", &home)
+        .env("TERM", "xterm-256color")
+        .env("COLUMNS", "80")
+        .env("LANG", std::env::var("LANG").unwrap_or_else(|_| "C.UTF-8".to_string()))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    for variable in ["PATH", "USER", "LOGNAME", "STARSHIP_CONFIG"] {
+        if let Some(value) = std::env::var_os(variable) {
+            command.env(variable, value);
+        }
+    }
+
+    let mut child = command.spawn().map_err(|e| format!("Failed to run zsh: {}", e))?;
+    let mut stdout = child.stdout.take().ok_or("Failed to capture zsh output")?;
+    let reader = thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        output
+    });
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() > PREVIEW_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err("Prompt preview timed out".to_string());
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(20)),
+            Err(e) => return Err(format!("Failed to wait for zsh: {}", e)),
+        }
+    }
+
+    let output = reader.join().map_err(|_| "Failed to read zsh output".to_string())?;
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+/// Render a theme's prompt (the active one by default) in a sandboxed `zsh -f`
+/// that loads only oh-my-zsh and the theme, without plugins or the user's rc files
+#[tauri::command]
+pub fn preview_theme(name: Option<String>) -> Result<PromptPreview, String> {
+    let status = detect_prompt()?;
+    // The name ends up in ZSH_THEME, which oh-my-zsh turns into a path to source
+    if let Some(name) = &name {
+        if !available_themes(status.theme.as_deref()).iter().any(|theme| theme.name == *name) {
+            return Err(format!("Theme '{}' is not installed", name));
+        }
+    }
+    let theme = name.or(status.theme.clone());
+
+    let script = match (&theme, &status.system) {
+        (Some(theme), _) => preview_script(theme),
+        // A hand-written prompt: replay its assignments only
+        (None, PromptSystem::Custom) => {
+            let file = status.defined_in.as_ref().map(|f| get_home_dir().join(f)).unwrap_or_default();
+            let content = read_config(&file)?.unwrap_or_default();
+            let re = Regex::new(r"^\s*(?:export\s+)?(?:PROMPT|PS1|RPROMPT|RPS1)=").unwrap();
+            let assignments: Vec<&str> = content.lines().filter(|line| re.is_match(line)).collect();
+            format!("setopt prompt_subst\n{}\n{}", assignments.join("\n"), PROMPT_RENDER)
+        }
+        (None, _) => return Err("No prompt theme is configured".to_string()),
+    };
+
+    let output = run_sandboxed_zsh(&script)?;
+    let (prompt, right_prompt) = output.split_once('\0').unwrap_or((output.as_str(), ""));
+
+    Ok(PromptPreview {
+        theme,
+        prompt: prompt.to_string(),
+        right_prompt: right_prompt.to_string(),
+    })
+}
@@ -2,9 +2,49 @@ mod commands;
 
 use commands::alias::{list_aliases, add_alias, update_alias, delete_alias, list_secrets_aliases};
 use commands::function::{list_functions, add_function, update_function, delete_function};
+use commands::autoload::{list_autoload_functions, add_autoload_function, update_autoload_function, delete_autoload_function, move_function};
 use commands::config::{get_config, update_config, reload_zsh};
 use commands::git::{git_status, git_pull, git_push, git_commit, git_log, git_diff, git_init};
-use commands::plugin::{get_plugins, get_popular_plugins, add_plugin, remove_plugin, open_url_wsl};
+use commands::plugin::{get_plugin_managers, get_plugins, get_popular_plugins, add_plugin, remove_plugin, set_plugin_enabled, get_plugin_order_warnings, open_url_wsl};
+use commands::plugin_install::{install_plugin, update_plugin, check_plugin_updates, uninstall_plugin};
+use commands::plugin_lock::{write_plugin_lock, check_plugin_lock, sync_plugin_lock};
+use commands::plugin_catalog::{get_plugin_catalog, search_plugins};
+use commands::plugin_definitions::{get_plugin_definitions, get_definition_conflicts};
+use commands::theme::{get_prompt_status, list_themes, set_theme, preview_theme};
+use commands::completion::{list_fpath, list_completions, list_compdefs, add_completion_file, add_compdef, remove_compdef, rebuild_completion_dump};
+use commands::navigation::{list_named_dirs, add_named_dir, update_named_dir, remove_named_dir, move_named_dir, list_cdpath, add_cdpath, update_cdpath, remove_cdpath, move_cdpath};
+use commands::diff::{git_semantic_diff, suggest_commit_message};
+use commands::conflict::{git_conflicts, git_resolve_conflict, git_continue, git_abort};
+use commands::merge::{merge_config_file, git_auto_merge_conflicts, install_merge_driver};
+use commands::branch::{git_branches, git_create_branch, git_switch_branch, git_remotes, git_add_remote, git_remove_remote, git_set_upstream, git_fetch};
+use commands::setup::git_clone_setup;
+use commands::gitignore::ensure_gitignore;
+use commands::history::{entry_history, restore_entry_version};
+use commands::snapshot::{git_show_file, git_restore_file, git_restore_snapshot, git_revert_commit, git_apply_stash, git_drop_stash};
+use commands::progress::git_cancel;
+use commands::secrets::scan_secrets;
+use commands::sync::{get_sync_status, set_sync_settings, sync_now};
+use commands::hooks::{git_hooks_status, install_git_hooks, uninstall_git_hooks};
+
+/// Handle headless invocations (e.g. from git) without starting the UI.
+/// Returns the process exit code, or None when the app should start normally.
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    match args.first().map(String::as_str) {
+        Some("merge-driver") if args.len() == 4 => {
+            Some(commands::merge::run_merge_driver(&args[1], &args[2], &args[3]))
+        }
+        Some("merge-driver") => {
+            eprintln!("Usage: merge-driver <base> <ours> <theirs>");
+            Some(2)
+        }
+        Some("hook") if args.len() >= 2 => Some(commands::hooks::run_hook(&args[1])),
+        Some("hook") => {
+            eprintln!("Usage: hook <pre-commit|pre-push>");
+            Some(2)
+        }
+        _ => None,
+    }
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -13,6 +53,10 @@ pub fn run() {
         .plugin(tauri_plugin_window_state::Builder::default().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
+        .setup(|app| {
+            commands::sync::start_sync_engine(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             list_aliases,
             add_alias,
@@ -23,6 +67,11 @@ pub fn run() {
             add_function,
             update_function,
             delete_function,
+            list_autoload_functions,
+            add_autoload_function,
+            update_autoload_function,
+            delete_autoload_function,
+            move_function,
             get_config,
             update_config,
             reload_zsh,
@@ -33,11 +82,81 @@ pub fn run() {
             git_log,
             git_diff,
             git_init,
+            get_plugin_managers,
             get_plugins,
             get_popular_plugins,
             add_plugin,
             remove_plugin,
+            set_plugin_enabled,
+            get_plugin_order_warnings,
             open_url_wsl,
+            install_plugin,
+            update_plugin,
+            check_plugin_updates,
+            uninstall_plugin,
+            write_plugin_lock,
+            check_plugin_lock,
+            sync_plugin_lock,
+            get_plugin_catalog,
+            search_plugins,
+            get_plugin_definitions,
+            get_definition_conflicts,
+            get_prompt_status,
+            list_themes,
+            set_theme,
+            preview_theme,
+            list_fpath,
+            list_completions,
+            list_compdefs,
+            add_completion_file,
+            add_compdef,
+            remove_compdef,
+            rebuild_completion_dump,
+            list_named_dirs,
+            add_named_dir,
+            update_named_dir,
+            remove_named_dir,
+            move_named_dir,
+            list_cdpath,
+            add_cdpath,
+            update_cdpath,
+            remove_cdpath,
+            move_cdpath,
+            git_semantic_diff,
+            suggest_commit_message,
+            git_conflicts,
+            git_resolve_conflict,
+            git_continue,
+            git_abort,
+            merge_config_file,
+            git_auto_merge_conflicts,
+            install_merge_driver,
+            git_branches,
+            git_create_branch,
+            git_switch_branch,
+            git_remotes,
+            git_add_remote,
+            git_remove_remote,
+            git_set_upstream,
+            git_fetch,
+            git_clone_setup,
+            ensure_gitignore,
+            entry_history,
+            restore_entry_version,
+            git_show_file,
+            git_restore_file,
+            git_restore_snapshot,
+            git_revert_commit,
+            git_apply_stash,
+            git_drop_stash,
+            git_cancel,
+            scan_secrets,
+            get_sync_status,
+            set_sync_settings,
+            sync_now,
+            git_hooks_status,
+            install_git_hooks,
+            uninstall_git_hooks,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -75,7 +75,7 @@ export function PluginsTab() {
 
   const handleAddPlugin = async (pluginName: string) => {
     try {
-      await addMutation.mutateAsync(pluginName);
+      await addMutation.mutateAsync({ pluginName, shared: false });
       toast.success("Plugin enabled successfully", {
         description: `${pluginName} has been added to your configuration`,
       });
@@ -87,9 +87,9 @@ export function PluginsTab() {
     }
   };
 
-  const handleRemovePlugin = async (pluginName: string) => {
+  const handleRemovePlugin = async (pluginName: string, shared: boolean) => {
     try {
-      await removeMutation.mutateAsync(pluginName);
+      await removeMutation.mutateAsync({ pluginName, shared });
       toast.success("Plugin disabled successfully", {
         description: `${pluginName} has been removed from your configuration`,
       });
@@ -250,7 +250,7 @@ export function PluginsTab() {
                       <Button
                         variant="ghost"
                         size="sm"
-                        onClick={() => handleRemovePlugin(plugin.name)}
+                        onClick={() => handleRemovePlugin(plugin.name, plugin.shared)}
                         disabled={removeMutation.isPending}
                       >
                         <Minus className="h-4 w-4 mr-1" />
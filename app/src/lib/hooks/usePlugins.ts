@@ -1,6 +1,6 @@
 import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
-import type { Plugin } from "../types";
+import type { CatalogEntry, DefinitionConflict, LockCheck, Plugin, PluginDefinitions, PluginLock, PluginManager, PluginOrderWarning } from "../types";
 
 // Query to get enabled plugins
 export function usePlugins() {
@@ -12,6 +12,66 @@ export function usePlugins() {
   });
 }
 
+// Query to list every oh-my-zsh plugin on disk plus the curated ones
+export function usePluginCatalog() {
+  return useQuery<CatalogEntry[]>({
+    queryKey: ["plugins", "catalog"],
+    queryFn: async () => {
+      return await invoke<CatalogEntry[]>("get_plugin_catalog");
+    },
+  });
+}
+
+// Query to search plugin names, descriptions and READMEs
+export function useSearchPlugins(query: string, limit?: number) {
+  return useQuery<CatalogEntry[]>({
+    queryKey: ["plugins", "catalog", query, limit],
+    queryFn: async () => {
+      return await invoke<CatalogEntry[]>("search_plugins", { query, limit });
+    },
+  });
+}
+
+// Query to list the aliases and functions each enabled plugin defines
+export function usePluginDefinitions() {
+  return useQuery<PluginDefinitions[]>({
+    queryKey: ["plugins", "definitions"],
+    queryFn: async () => {
+      return await invoke<PluginDefinitions[]>("get_plugin_definitions");
+    },
+  });
+}
+
+// Query to find our aliases and functions that collide with plugin definitions
+export function useDefinitionConflicts() {
+  return useQuery<DefinitionConflict[]>({
+    queryKey: ["plugins", "conflicts"],
+    queryFn: async () => {
+      return await invoke<DefinitionConflict[]>("get_definition_conflicts");
+    },
+  });
+}
+
+// Query to get oh-my-zsh plugins loaded in an order that breaks them
+export function usePluginOrderWarnings() {
+  return useQuery<PluginOrderWarning[]>({
+    queryKey: ["plugins", "order"],
+    queryFn: async () => {
+      return await invoke<PluginOrderWarning[]>("get_plugin_order_warnings");
+    },
+  });
+}
+
+// Query to get the plugin managers detected on this machine
+export function usePluginManagers() {
+  return useQuery<PluginManager[]>({
+    queryKey: ["plugins", "managers"],
+    queryFn: async () => {
+      return await invoke<PluginManager[]>("get_plugin_managers");
+    },
+  });
+}
+
 // Query to get popular/available plugins
 export function usePopularPlugins() {
   return useQuery<Plugin[]>({
@@ -27,8 +87,8 @@ export function useAddPlugin() {
   const queryClient = useQueryClient();
 
   return useMutation({
-    mutationFn: async (pluginName: string) => {
-      await invoke("add_plugin", { pluginName });
+    mutationFn: async ({ pluginName, shared }: { pluginName: string; shared: boolean }) => {
+      await invoke("add_plugin", { pluginName, shared });
     },
     onSuccess: () => {
       // Invalidate both enabled and popular plugins
@@ -42,8 +102,8 @@ export function useRemovePlugin() {
   const queryClient = useQueryClient();
 
   return useMutation({
-    mutationFn: async (pluginName: string) => {
-      await invoke("remove_plugin", { pluginName });
+    mutationFn: async ({ pluginName, shared }: { pluginName: string; shared: boolean }) => {
+      await invoke("remove_plugin", { pluginName, shared });
     },
     onSuccess: () => {
       // Invalidate both enabled and popular plugins
@@ -51,3 +111,111 @@ export function useRemovePlugin() {
     },
   });
 }
+
+// Mutation to enable or disable a plugin without removing it
+export function useSetPluginEnabled() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async ({ pluginName, enabled }: { pluginName: string; enabled: boolean }) => {
+      await invoke("set_plugin_enabled", { pluginName, enabled });
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["plugins"] });
+    },
+  });
+}
+
+// Mutation to clone a plugin into ~/.oh-my-zsh/custom/plugins
+export function useInstallPlugin() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async ({ url, name, operationId }: { url: string; name?: string; operationId?: string }) => {
+      return await invoke<Plugin>("install_plugin", { url, name, operationId });
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["plugins"] });
+    },
+  });
+}
+
+// Mutation to pull the latest version of an installed plugin
+export function useUpdatePlugin() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async ({ name, operationId }: { name: string; operationId?: string }) => {
+      return await invoke<Plugin>("update_plugin", { name, operationId });
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["plugins"] });
+    },
+  });
+}
+
+// Mutation to fetch installed plugins and refresh their update_available flag
+export function useCheckPluginUpdates() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (operationId?: string) => {
+      return await invoke<Plugin[]>("check_plugin_updates", { operationId });
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["plugins"] });
+    },
+  });
+}
+
+// Mutation to delete an installed plugin
+export function useUninstallPlugin() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (name: string) => {
+      await invoke("uninstall_plugin", { name });
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["plugins"] });
+    },
+  });
+}
+
+// Query to compare installed plugins with ~/.zsh/plugins.lock
+export function usePluginLock() {
+  return useQuery<LockCheck[]>({
+    queryKey: ["plugins", "lock"],
+    queryFn: async () => {
+      return await invoke<LockCheck[]>("check_plugin_lock");
+    },
+  });
+}
+
+// Mutation to record the installed commits of enabled plugins in the lockfile
+export function useWritePluginLock() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async () => {
+      return await invoke<PluginLock>("write_plugin_lock");
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["plugins"] });
+    },
+  });
+}
+
+// Mutation to install or check out the locked commit of every locked plugin
+export function useSyncPluginLock() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (operationId?: string) => {
+      return await invoke<LockCheck[]>("sync_plugin_lock", { operationId });
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["plugins"] });
+    },
+  });
+}
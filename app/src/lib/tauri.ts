@@ -2,7 +2,8 @@
 // These will call the Rust backend commands
 
 import { invoke } from "@tauri-apps/api/core";
-import type { Alias, ShellFunction, GitStatus, GitCommit, MergeStrategy, ConfigContent } from "./types";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { Alias, ShellFunction, AutoloadFunction, GitStatus, GitCommit, MergeStrategy, ConfigContent, SemanticChange, ConflictState, ConflictResolution, MergeResult, GitBranch, GitRemote, SetupReport, SetupStep, EntryKind, EntryVersion, RestoreResult, GitProgress, SecretFinding, SyncSettings, SyncStatus, SyncEvent, GitHook, PromptStatus, Theme, PromptPreview, FpathDir, CompletionFunction, Compdef, CompdumpRebuild, NamedDir, CdpathEntry } from "./types";
 
 // Alias commands
 export async function listAliases(shared: boolean): Promise<Alias[]> {
@@ -42,6 +43,26 @@ export async function deleteFunction(name: string, shared: boolean): Promise<voi
   return invoke("delete_function", { name, shared });
 }
 
+export async function listAutoloadFunctions(shared: boolean): Promise<AutoloadFunction[]> {
+  return invoke("list_autoload_functions", { shared });
+}
+
+export async function addAutoloadFunction(name: string, content: string, shared: boolean): Promise<AutoloadFunction> {
+  return invoke("add_autoload_function", { name, content, shared });
+}
+
+export async function updateAutoloadFunction(name: string, content: string, shared: boolean): Promise<AutoloadFunction> {
+  return invoke("update_autoload_function", { name, content, shared });
+}
+
+export async function deleteAutoloadFunction(name: string, shared: boolean): Promise<void> {
+  return invoke("delete_autoload_function", { name, shared });
+}
+
+export async function moveFunction(name: string, shared: boolean, toAutoload: boolean): Promise<void> {
+  return invoke("move_function", { name, shared, toAutoload });
+}
+
 // Config commands
 export async function getConfig(shared: boolean): Promise<ConfigContent> {
   return invoke("get_config", { shared });
@@ -69,12 +90,12 @@ export async function gitStatus(): Promise<GitStatus> {
   return invoke("git_status");
 }
 
-export async function gitPull(): Promise<string> {
-  return invoke("git_pull");
+export async function gitPull(operationId?: string, timeoutSecs?: number): Promise<string> {
+  return invoke("git_pull", { operationId, timeoutSecs });
 }
 
-export async function gitPush(): Promise<string> {
-  return invoke("git_push");
+export async function gitPush(operationId?: string, timeoutSecs?: number): Promise<string> {
+  return invoke("git_push", { operationId, timeoutSecs });
 }
 
 export async function gitCommit(message: string): Promise<string> {
@@ -92,3 +113,242 @@ export async function gitDiff(): Promise<string> {
 export async function gitInit(): Promise<string> {
   return invoke("git_init");
 }
+
+export async function gitSemanticDiff(from?: string, to?: string, staged: boolean = false): Promise<SemanticChange[]> {
+  return invoke("git_semantic_diff", { from, to, staged });
+}
+
+export async function suggestCommitMessage(): Promise<string> {
+  return invoke("suggest_commit_message");
+}
+
+export async function gitConflicts(): Promise<ConflictState> {
+  return invoke("git_conflicts");
+}
+
+export async function gitResolveConflict(path: string, resolution: ConflictResolution): Promise<void> {
+  return invoke("git_resolve_conflict", { path, resolution });
+}
+
+export async function gitContinue(): Promise<string> {
+  return invoke("git_continue");
+}
+
+export async function gitAbort(): Promise<string> {
+  return invoke("git_abort");
+}
+
+export async function mergeConfigFile(base: string, ours: string, theirs: string): Promise<MergeResult> {
+  return invoke("merge_config_file", { base, ours, theirs });
+}
+
+export async function gitAutoMergeConflicts(): Promise<string[]> {
+  return invoke("git_auto_merge_conflicts");
+}
+
+export async function installMergeDriver(): Promise<string> {
+  return invoke("install_merge_driver");
+}
+
+export async function gitBranches(): Promise<GitBranch[]> {
+  return invoke("git_branches");
+}
+
+export async function gitCreateBranch(name: string, checkout: boolean): Promise<string> {
+  return invoke("git_create_branch", { name, checkout });
+}
+
+export async function gitSwitchBranch(name: string): Promise<string> {
+  return invoke("git_switch_branch", { name });
+}
+
+export async function gitRemotes(): Promise<GitRemote[]> {
+  return invoke("git_remotes");
+}
+
+export async function gitAddRemote(name: string, url: string): Promise<void> {
+  return invoke("git_add_remote", { name, url });
+}
+
+export async function gitRemoveRemote(name: string): Promise<void> {
+  return invoke("git_remove_remote", { name });
+}
+
+export async function gitSetUpstream(remote: string, branch?: string, operationId?: string): Promise<string> {
+  return invoke("git_set_upstream", { remote, branch, operationId });
+}
+
+export async function gitFetch(remote?: string, operationId?: string, timeoutSecs?: number): Promise<string> {
+  return invoke("git_fetch", { remote, operationId, timeoutSecs });
+}
+
+export async function gitCloneSetup(url: string, branch?: string, operationId?: string): Promise<SetupReport> {
+  return invoke("git_clone_setup", { url, branch, operationId });
+}
+
+export async function gitCancel(operationId: string): Promise<void> {
+  return invoke("git_cancel", { operationId });
+}
+
+export async function onGitProgress(handler: (progress: GitProgress) => void): Promise<UnlistenFn> {
+  return listen<GitProgress>("git-progress", (event) => handler(event.payload));
+}
+
+export async function onSetupStep(handler: (step: SetupStep) => void): Promise<UnlistenFn> {
+  return listen<SetupStep>("setup-step", (event) => handler(event.payload));
+}
+
+export async function ensureGitignore(): Promise<string[]> {
+  return invoke("ensure_gitignore");
+}
+
+export async function entryHistory(kind: EntryKind, name: string): Promise<EntryVersion[]> {
+  return invoke("entry_history", { kind, name });
+}
+
+export async function restoreEntryVersion(kind: EntryKind, name: string, commit: string): Promise<void> {
+  return invoke("restore_entry_version", { kind, name, commit });
+}
+
+export async function gitShowFile(commit: string, path: string): Promise<string> {
+  return invoke("git_show_file", { commit, path });
+}
+
+export async function gitRestoreFile(commit: string, path: string): Promise<RestoreResult> {
+  return invoke("git_restore_file", { commit, path });
+}
+
+export async function gitRestoreSnapshot(commit: string): Promise<RestoreResult> {
+  return invoke("git_restore_snapshot", { commit });
+}
+
+export async function gitRevertCommit(commit: string): Promise<RestoreResult> {
+  return invoke("git_revert_commit", { commit });
+}
+
+export async function gitApplyStash(hash: string): Promise<string> {
+  return invoke("git_apply_stash", { hash });
+}
+
+export async function gitDropStash(hash: string): Promise<void> {
+  return invoke("git_drop_stash", { hash });
+}
+
+export async function scanSecrets(): Promise<SecretFinding[]> {
+  return invoke("scan_secrets");
+}
+
+export async function getSyncStatus(): Promise<SyncStatus> {
+  return invoke("get_sync_status");
+}
+
+export async function setSyncSettings(settings: SyncSettings): Promise<void> {
+  return invoke("set_sync_settings", { settings });
+}
+
+export async function syncNow(): Promise<void> {
+  return invoke("sync_now");
+}
+
+export async function onSyncEvent(handler: (event: SyncEvent) => void): Promise<UnlistenFn> {
+  return listen<SyncEvent>("sync-event", (event) => handler(event.payload));
+}
+
+export async function gitHooksStatus(): Promise<GitHook[]> {
+  return invoke("git_hooks_status");
+}
+
+export async function installGitHooks(): Promise<GitHook[]> {
+  return invoke("install_git_hooks");
+}
+
+export async function uninstallGitHooks(): Promise<GitHook[]> {
+  return invoke("uninstall_git_hooks");
+}
+
+// Theme commands
+export async function getPromptStatus(): Promise<PromptStatus> {
+  return invoke("get_prompt_status");
+}
+
+export async function listThemes(): Promise<Theme[]> {
+  return invoke("list_themes");
+}
+
+export async function setTheme(name: string): Promise<PromptStatus> {
+  return invoke("set_theme", { name });
+}
+
+export async function previewTheme(name?: string): Promise<PromptPreview> {
+  return invoke("preview_theme", { name });
+}
+
+// Completion commands
+export async function listFpath(): Promise<FpathDir[]> {
+  return invoke("list_fpath");
+}
+
+export async function listCompletions(): Promise<CompletionFunction[]> {
+  return invoke("list_completions");
+}
+
+export async function listCompdefs(): Promise<Compdef[]> {
+  return invoke("list_compdefs");
+}
+
+export async function addCompletionFile(name: string, content: string, overwrite: boolean): Promise<CompletionFunction> {
+  return invoke("add_completion_file", { name, content, overwrite });
+}
+
+export async function addCompdef(command: string, completion: string): Promise<Compdef> {
+  return invoke("add_compdef", { command, completion });
+}
+
+export async function removeCompdef(command: string): Promise<void> {
+  return invoke("remove_compdef", { command });
+}
+
+export async function rebuildCompletionDump(): Promise<CompdumpRebuild> {
+  return invoke("rebuild_completion_dump");
+}
+
+// Navigation commands
+export async function listNamedDirs(): Promise<NamedDir[]> {
+  return invoke("list_named_dirs");
+}
+
+export async function addNamedDir(name: string, path: string, shared: boolean): Promise<NamedDir> {
+  return invoke("add_named_dir", { name, path, shared });
+}
+
+export async function updateNamedDir(name: string, newName: string, path: string, shared: boolean): Promise<NamedDir> {
+  return invoke("update_named_dir", { name, newName, path, shared });
+}
+
+export async function removeNamedDir(name: string, shared: boolean): Promise<void> {
+  return invoke("remove_named_dir", { name, shared });
+}
+
+export async function moveNamedDir(name: string, shared: boolean): Promise<NamedDir> {
+  return invoke("move_named_dir", { name, shared });
+}
+
+export async function listCdpath(): Promise<CdpathEntry[]> {
+  return invoke("list_cdpath");
+}
+
+export async function addCdpath(path: string, shared: boolean): Promise<CdpathEntry> {
+  return invoke("add_cdpath", { path, shared });
+}
+
+export async function updateCdpath(path: string, newPath: string, shared: boolean): Promise<CdpathEntry> {
+  return invoke("update_cdpath", { path, newPath, shared });
+}
+
+export async function removeCdpath(path: string, shared: boolean): Promise<void> {
+  return invoke("remove_cdpath", { path, shared });
+}
+
+export async function moveCdpath(path: string, shared: boolean): Promise<CdpathEntry> {
+  return invoke("move_cdpath", { path, shared });
+}
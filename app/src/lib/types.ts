@@ -12,6 +12,13 @@ export interface ShellFunction {
   shared: boolean;
 }
 
+export interface AutoloadFunction {
+  name: string;
+  content: string;
+  shared: boolean;
+  file: string;
+}
+
 export interface ConfigContent {
   content: string;
   shared: boolean;
@@ -27,6 +34,11 @@ export interface Config {
   historySize: number;
 }
 
+export interface GitRename {
+  from: string;
+  to: string;
+}
+
 export interface GitStatus {
   branch: string;
   clean: boolean;
@@ -34,8 +46,46 @@ export interface GitStatus {
   behind: number;
   modified: string[];
   untracked: string[];
+  staged: string[];
+  unstaged: string[];
+  conflicted: string[];
+  renamed: GitRename[];
+  upstream?: string;
+  operation?: GitOperation;
+  policy_violations: string[];
+}
+
+export interface GitBranch {
+  name: string;
+  current: boolean;
+  remote: boolean;
+  upstream?: string;
+}
+
+export interface GitRemote {
+  name: string;
+  url: string;
+}
+
+export type GitOperation = 'rebase' | 'merge' | 'revert';
+
+export interface ConflictFile {
+  path: string;
+  base?: string;
+  ours?: string;
+  theirs?: string;
+}
+
+export interface ConflictState {
+  operation?: GitOperation;
+  files: ConflictFile[];
 }
 
+export type ConflictResolution =
+  | { strategy: 'ours' }
+  | { strategy: 'theirs' }
+  | { strategy: 'merged'; content: string };
+
 export interface GitCommit {
   hash: string;
   message: string;
@@ -43,6 +93,171 @@ export interface GitCommit {
   date: string;
 }
 
+export type EntryKind = 'alias' | 'function' | 'plugin';
+
+export type ChangeKind = 'added' | 'removed' | 'modified';
+
+export interface SemanticChange {
+  file: string;
+  kind: EntryKind;
+  change: ChangeKind;
+  name: string;
+  old_value?: string;
+  new_value?: string;
+  summary: string;
+}
+
+export interface EntryVersion {
+  commit: GitCommit;
+  value?: string;
+}
+
+export interface RestoreResult {
+  output: string;
+  stash?: string;
+}
+
+export type ChunkKind = 'alias' | 'function' | 'plugins' | 'line' | 'trailer';
+
+export interface MergeConflict {
+  kind: ChunkKind;
+  name: string;
+  base?: string;
+  ours?: string;
+  theirs?: string;
+}
+
+export interface MergeResult {
+  content: string;
+  conflicts: MergeConflict[];
+}
+
+export type SetupStatus = 'done' | 'skipped' | 'conflict';
+
+export interface SetupStep {
+  step: string;
+  status: SetupStatus;
+  message: string;
+}
+
+export interface SetupReport {
+  steps: SetupStep[];
+  backup_dir?: string;
+}
+
+export type ProgressKind = 'started' | 'progress' | 'credentials-required' | 'done' | 'failed' | 'cancelled' | 'timed-out';
+
+export interface GitProgress {
+  operation_id: string;
+  kind: ProgressKind;
+  message: string;
+}
+
+export interface SecretFinding {
+  file: string;
+  line: number;
+  rule: string;
+  excerpt: string;
+}
+
+export type SyncPolicy = 'manual' | 'commit-only' | 'full';
+
+export interface SyncSettings {
+  policy: SyncPolicy;
+  interval_secs: number;
+  commit_delay_secs: number;
+}
+
+export type SyncAction = 'committed' | 'pulled' | 'pushed' | 'paused' | 'resumed' | 'failed';
+
+export interface SyncEvent {
+  action: SyncAction;
+  message: string;
+  timestamp: number;
+}
+
+export interface SyncStatus {
+  settings: SyncSettings;
+  paused_reason?: string;
+  last_event?: SyncEvent;
+}
+
+export interface GitHook {
+  name: string;
+  installed: boolean;
+  previous: boolean;
+}
+
+export type PromptSystem = 'oh-my-zsh' | 'powerlevel10k' | 'starship' | 'custom' | 'none';
+
+export interface Theme {
+  name: string;
+  system: PromptSystem;
+  builtin: boolean;
+  active: boolean;
+}
+
+export interface PromptStatus {
+  system: PromptSystem;
+  theme?: string;
+  config_file?: string;
+  defined_in?: string;
+}
+
+export interface PromptPreview {
+  theme?: string;
+  prompt: string;
+  right_prompt: string;
+}
+
+export interface FpathDir {
+  path: string;
+  source: string;
+  exists: boolean;
+  managed: boolean;
+  completions: number;
+}
+
+export interface CompletionFunction {
+  name: string;
+  path: string;
+  commands: string[];
+}
+
+export interface Compdef {
+  command: string;
+  completion: string;
+  service: boolean;
+  file: string;
+  line: number;
+}
+
+export interface CompdumpRebuild {
+  removed: string[];
+  regenerated: boolean;
+}
+
+export interface NamedDir {
+  name: string;
+  path: string;
+  expanded: string;
+  exists: boolean;
+  portable: boolean;
+  shared: boolean;
+  file: string;
+  line: number;
+}
+
+export interface CdpathEntry {
+  path: string;
+  expanded: string;
+  exists: boolean;
+  portable: boolean;
+  shared: boolean;
+  file: string;
+  line: number;
+}
+
 export interface ExportData {
   aliases: Alias[];
   functions: ShellFunction[];
@@ -52,7 +267,7 @@ export interface ExportData {
 
 export type MergeStrategy = 'overwrite' | 'keep' | 'ask';
 
-export type PluginManager = 'oh-my-zsh' | 'zinit' | 'custom';
+export type PluginManager = 'oh-my-zsh' | 'zinit' | 'antidote' | 'antigen' | 'zplug' | 'sheldon' | 'custom';
 
 export interface Plugin {
   name: string;
@@ -61,5 +276,72 @@ export interface Plugin {
   description?: string;
   repository?: string;
   manager: PluginManager;
+  shared: boolean;
   install_command?: string;
+  version?: string;
+  commit?: string;
+  update_available: boolean;
+  lock_state?: LockState;
+}
+
+export interface CatalogEntry {
+  name: string;
+  description?: string;
+  builtin: boolean;
+  installed: boolean;
+  enabled: boolean;
+  repository?: string;
+  install_command?: string;
+}
+
+export interface PluginOrderWarning {
+  plugin: string;
+  message: string;
+}
+
+export type LockState = 'matches' | 'differs' | 'not-installed';
+
+export interface LockedPlugin {
+  name: string;
+  repository: string;
+  commit: string;
+}
+
+export interface PluginLock {
+  version: number;
+  plugins: LockedPlugin[];
+}
+
+export type DefinitionKind = 'alias' | 'function';
+
+export interface PluginAlias {
+  name: string;
+  command: string;
+}
+
+export interface PluginDefinitions {
+  plugin: string;
+  file?: string;
+  aliases: PluginAlias[];
+  functions: string[];
+}
+
+export interface Definition {
+  kind: DefinitionKind;
+  plugin?: string;
+  file: string;
+  command?: string;
+}
+
+export interface DefinitionConflict {
+  name: string;
+  definitions: Definition[];
+  winner: Definition;
+}
+
+export interface LockCheck {
+  name: string;
+  state: LockState;
+  locked_commit: string;
+  installed_commit?: string;
 }